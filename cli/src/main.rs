@@ -1,10 +1,16 @@
 use clap::Clap;
 use flume;
 use gui::core::gui::RacePlot;
-use racesim::post::race_result::RaceResult;
+use helpers::general::derive_seed;
+use racesim::core::race::FcyPhaseType;
+use racesim::core::telemetry::TelemetryOpts;
+use racesim::opt::strategy_opt::{print_strategy_ranking, rank_strategies, StrategyOptimizer};
+use racesim::post::mc_result::McResult;
+use racesim::post::race_result::{write_race_events, write_race_results, RaceResult};
 use racesim::pre::check_sim_opts_pars::check_sim_opts_pars;
 use racesim::pre::read_sim_pars::read_sim_pars;
-use racesim::pre::sim_opts::SimOpts;
+use racesim::pre::read_sim_pars::SimPars;
+use racesim::pre::sim_opts::{OptStrategyOpts, SimOpts, SubCommand};
 use rayon::prelude::*;
 use std::cmp::min;
 use std::thread;
@@ -25,13 +31,32 @@ fn main() -> anyhow::Result<()> {
     // create vector for the race result and simulate race(s)
     let mut race_results: Vec<RaceResult> = Vec::with_capacity(sim_opts.no_sim_runs as usize);
 
+    // determine base seed (drawn randomly if not set, it is printed such that the simulation can
+    // be replayed)
+    let seed = sim_opts.seed.unwrap_or_else(rand::random);
+
     // print race details
     println!(
         "INFO: Simulating {} {} with a time step size of {:.3}s",
         sim_pars.track_pars.name, sim_pars.race_pars.season, sim_opts.timestep_size
     );
+    println!("INFO: Base seed is {}", seed);
+
+    // STRATEGY OPTIMIZATION -----------------------------------------------------------------------
+    if let Some(SubCommand::OptStrategy(opt_strategy_opts)) = sim_opts.subcmd.as_ref() {
+        return optimize_strategy(&sim_opts, opt_strategy_opts, &sim_pars, seed);
+    }
 
     // EXECUTION -----------------------------------------------------------------------------------
+    // set telemetry options if the telemetry should be recorded
+    let telemetry_opts = sim_opts
+        .telemetry_path
+        .as_ref()
+        .map(|telemetry_path| TelemetryOpts {
+            path: telemetry_path.to_owned(),
+            decimation: sim_opts.telemetry_decimation,
+        });
+
     if !sim_opts.gui {
         // NON-GUI CASE ----------------------------------------------------------------------------
         let t_start = Instant::now();
@@ -42,9 +67,10 @@ fn main() -> anyhow::Result<()> {
                 racesim::core::handle_race::handle_race(
                     &sim_pars,
                     sim_opts.timestep_size,
-                    sim_opts.debug,
+                    seed,
                     None,
                     1.0,
+                    telemetry_opts.as_ref(),
                 )
                 .unwrap(),
             );
@@ -56,14 +82,18 @@ fn main() -> anyhow::Result<()> {
                 // calculate number of simulation runs to execute in current loop
                 let tmp_no_sim_runs = min(no_races_left, MAX_NO_CONCURRENT_JOBS);
 
-                // simulate the races and save the results
-                race_results.par_extend((0..tmp_no_sim_runs).into_par_iter().map(|_| {
+                // simulate the races and save the results (every run gets a seed derived from the
+                // base seed and its run index such that it can be replayed exactly)
+                let run_idx_offset = sim_opts.no_sim_runs - no_races_left;
+
+                race_results.par_extend((0..tmp_no_sim_runs).into_par_iter().map(|i| {
                     racesim::core::handle_race::handle_race(
                         &sim_pars,
                         sim_opts.timestep_size,
-                        false,
+                        derive_seed(seed, (run_idx_offset + i) as u64),
                         None,
                         1.0,
+                        None,
                     )
                     .unwrap()
                 }));
@@ -91,9 +121,10 @@ fn main() -> anyhow::Result<()> {
             racesim::core::handle_race::handle_race(
                 &sim_pars_thread,
                 sim_opts_thread.timestep_size,
-                sim_opts_thread.debug,
+                seed,
                 Some(&tx),
                 sim_opts_thread.realtime_factor,
+                telemetry_opts.as_ref(),
             )
         });
 
@@ -119,9 +150,108 @@ fn main() -> anyhow::Result<()> {
     // print results
     if race_results.len() == 1 {
         race_results[0].print_lap_and_race_times();
-    } else {
-        // TODO IMPLEMENTATION MISSING
+        race_results[0].print_sector_times();
+        race_results[0].print_race_events(sim_opts.debug);
+
+        if !race_results[0].regulation_violations.is_empty() {
+            race_results[0].print_regulation_violations();
+        }
+
+        if !race_results[0].penalties.is_empty() {
+            race_results[0].print_penalties();
+        }
+
+        if !race_results[0].pit_stops.is_empty() {
+            race_results[0].print_pit_stops();
+        }
+    } else if race_results.len() > 1 {
+        McResult::new(&race_results).print_summary("Monte Carlo summary");
+
+        // split results by races with and without safety car phase (if both occurred)
+        let (race_results_sc, race_results_no_sc): (Vec<&RaceResult>, Vec<&RaceResult>) =
+            race_results
+                .iter()
+                .partition(|race_result| race_result.get_fcy_phase_occurred(&FcyPhaseType::Sc));
+
+        if !race_results_sc.is_empty() && !race_results_no_sc.is_empty() {
+            McResult::new(race_results_sc).print_summary("Monte Carlo summary of races with SC");
+            McResult::new(race_results_no_sc)
+                .print_summary("Monte Carlo summary of races without SC");
+        }
+    }
+
+    // write race results to a file if indicated
+    if let Some(output_path) = sim_opts.output_path.as_ref() {
+        if !race_results.is_empty() {
+            write_race_results(&race_results, output_path.as_path())?;
+        }
     }
 
+    // write race events to a file if indicated
+    if let Some(events_path) = sim_opts.events_path.as_ref() {
+        if !race_results.is_empty() {
+            write_race_events(&race_results, events_path.as_path())?;
+        }
+    }
+
+    // write aggregated results to a file if indicated
+    if let Some(mc_result_path) = sim_opts.mc_result_path.as_ref() {
+        if !race_results.is_empty() {
+            McResult::new(&race_results).write_json(mc_result_path.as_path())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// optimize_strategy determines the strategy candidates of the car, evaluates them with full race
+/// simulations, and prints the resulting ranking. All candidates are evaluated with the same seeds
+/// such that they are compared under the same random influences.
+fn optimize_strategy(
+    sim_opts: &SimOpts,
+    opt_strategy_opts: &OptStrategyOpts,
+    sim_pars: &SimPars,
+    seed: u64,
+) -> anyhow::Result<()> {
+    let t_start = Instant::now();
+
+    // determine strategy candidates
+    let strategy_optimizer = StrategyOptimizer::new(
+        sim_pars,
+        opt_strategy_opts.car_no,
+        opt_strategy_opts.max_no_stops,
+        opt_strategy_opts.min_stint_length,
+        opt_strategy_opts.no_candidates,
+    )?;
+    let candidates = strategy_optimizer.get_candidates();
+
+    println!(
+        "INFO: Evaluating {} strategy candidates of car {}",
+        candidates.len(),
+        opt_strategy_opts.car_no
+    );
+
+    // evaluate candidates by full race simulations (a single run uses the seed directly)
+    let seeds: Vec<u64> = if sim_opts.no_sim_runs == 1 {
+        vec![seed]
+    } else {
+        (0..sim_opts.no_sim_runs)
+            .map(|i| derive_seed(seed, i as u64))
+            .collect()
+    };
+
+    let mut evaluations = candidates
+        .par_iter()
+        .map(|candidate| strategy_optimizer.evaluate(candidate, sim_opts.timestep_size, &seeds))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // rank and print the strategies
+    rank_strategies(&mut evaluations);
+    print_strategy_ranking(&evaluations, opt_strategy_opts.car_no, sim_opts.no_sim_runs);
+
+    println!(
+        "INFO: Execution time (total): {}ms",
+        t_start.elapsed().as_millis()
+    );
     Ok(())
 }
@@ -272,6 +272,22 @@ impl RacePlot {
             ));
         }
 
+        // add safety car point (if the safety car is on the track)
+        if let Some(sc_race_prog) = self.racesim_interface.race_state.sc_race_prog {
+            let tmp_dists = self.track.get_dists_for_race_progs(&[sc_race_prog]);
+            let tmp_coords = self.track.get_coords_for_dists(&tmp_dists);
+
+            shapes.push(egui::Shape::circle_filled(
+                to_screen
+                    * egui::Pos2 {
+                        x: tmp_coords[0].x as f32,
+                        y: tmp_coords[0].y as f32,
+                    },
+                7.0,
+                egui::Color32::from_rgb(255, 215, 0),
+            ));
+        }
+
         // UPDATE GENERAL INFORMATION TEXT IN GUI --------------------------------------------------
         // add current lap
         let race_progs: Vec<f64> = self
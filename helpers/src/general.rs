@@ -29,6 +29,21 @@ pub fn argmax<T: std::cmp::PartialOrd>(x: &[T]) -> usize {
     max_idx
 }
 
+/// argmin returns the index of the minimum value in the array x.
+pub fn argmin<T: std::cmp::PartialOrd>(x: &[T]) -> usize {
+    let (min_idx, _min_val) =
+        x.iter()
+            .enumerate()
+            .fold((0, &x[0]), |(idx_min, val_min), (idx, val)| {
+                if val_min < val {
+                    (idx_min, val_min)
+                } else {
+                    (idx, val)
+                }
+            });
+    min_idx
+}
+
 /// max returns the maximum value in the array x.
 pub fn max<T: std::cmp::PartialOrd + std::marker::Copy>(x: &[T]) -> T {
     let &max_val = x.iter().fold(
@@ -80,3 +95,34 @@ pub fn lin_interp(x: f64, xp: &[f64], fp: &[f64]) -> f64 {
 
     *fp.last().unwrap()
 }
+
+/// percentile returns the q-th percentile (q in [0.0, 100.0]) of the values in the array x using
+/// linear interpolation between the closest ranks. Inspired by numpy.percentile.
+pub fn percentile(x: &[f64], q: f64) -> f64 {
+    if x.is_empty() {
+        panic!("Cannot calculate a percentile of an empty array!")
+    }
+
+    if !(0.0..=100.0).contains(&q) {
+        panic!("Percentile must be in the range [0.0, 100.0]!")
+    }
+
+    let mut x_sorted = x.to_vec();
+    x_sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let rank = q / 100.0 * (x_sorted.len() - 1) as f64;
+    let idx_low = rank.floor() as usize;
+    let idx_high = rank.ceil() as usize;
+
+    x_sorted[idx_low] + (rank - idx_low as f64) * (x_sorted[idx_high] - x_sorted[idx_low])
+}
+
+/// derive_seed returns a seed for the random number generator of a single simulation run on the
+/// basis of a base seed and the index of the run. It uses the SplitMix64 mixing function such that
+/// the derived seeds of consecutive runs are uncorrelated.
+pub fn derive_seed(base_seed: u64, idx: u64) -> u64 {
+    let mut z = base_seed.wrapping_add((idx + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -34,7 +34,9 @@ mod buffer_tests {
 
 #[cfg(test)]
 mod general_tests {
-    use crate::general::{argmax, argsort, lin_interp, max, SortOrder};
+    use crate::general::{
+        argmax, argmin, argsort, derive_seed, lin_interp, max, percentile, SortOrder,
+    };
     use approx::assert_ulps_eq;
 
     #[test]
@@ -48,6 +50,17 @@ mod general_tests {
         assert_eq!(argmax(&x), 3);
     }
 
+    #[test]
+    fn test_argmin_1() {
+        let x: Vec<i32> = vec![3, -1, 5, 8, -2];
+        assert_eq!(argmin(&x), 4);
+    }
+    #[test]
+    fn test_argmin_2() {
+        let x: Vec<f64> = vec![3.0, -1.0, 5.0, 8.0, -2.0];
+        assert_eq!(argmin(&x), 4);
+    }
+
     #[test]
     fn test_max_1() {
         let x: Vec<i32> = vec![3, -1, 5, 8, -2];
@@ -98,6 +111,33 @@ mod general_tests {
         let fp: Vec<f64> = vec![-1.0, -2.0, -1.0, 0.0];
         assert_ulps_eq!(lin_interp(7.5, &xp, &fp), -0.5);
     }
+
+    #[test]
+    fn test_percentile_1() {
+        let x: Vec<f64> = vec![3.0, -1.0, 5.0, 8.0, -2.0];
+        assert_ulps_eq!(percentile(&x, 50.0), 3.0);
+    }
+    #[test]
+    fn test_percentile_2() {
+        let x: Vec<f64> = vec![3.0, -1.0, 5.0, 8.0, -2.0];
+        assert_ulps_eq!(percentile(&x, 0.0), -2.0);
+        assert_ulps_eq!(percentile(&x, 100.0), 8.0);
+    }
+    #[test]
+    fn test_percentile_3() {
+        let x: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+        assert_ulps_eq!(percentile(&x, 25.0), 1.75);
+    }
+
+    #[test]
+    fn test_derive_seed_1() {
+        assert_eq!(derive_seed(42, 3), derive_seed(42, 3));
+    }
+    #[test]
+    fn test_derive_seed_2() {
+        assert_ne!(derive_seed(42, 0), derive_seed(42, 1));
+        assert_ne!(derive_seed(42, 0), derive_seed(43, 0));
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
 use crate::core::driver::Driver;
+use crate::core::penalties::{Penalty, PenaltyEntry, PenaltyPars, PenaltyType};
 use crate::core::state_handler::StateHandler;
-use crate::core::tireset::Tireset;
+use crate::core::strategy_controller::{
+    create_strategy_controller, StrategyController, StrategyControllerPars, StrategyInfo,
+};
+use crate::core::tireset::{Tireset, TiresetPars};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -33,7 +37,20 @@ pub struct StrategyEntry {
 /// * `pit_location` - (m) Location of the pit (must be within the pit lane)
 /// * `strategy` - List that contains the strategy entries that determine the pit stops during the
 /// race
-/// * `p_grid` - Grid position at the race start
+/// * `p_grid` - Qualifying position, i.e. grid position at the race start without grid penalties
+/// * `grid_penalty` - Grid penalty that is applied on top of the qualifying position -> places
+/// (the car is moved back by the given number of grid positions), back_of_grid, pitlane_start (the
+/// car starts from the pit lane after the field passed the pit exit), set null for no grid penalty
+/// * `p_failure` - Probability of a technical failure per lap (overrides the season-specific
+/// probability in the race parameters if set)
+/// * `dnf_lap` - Lap in which the car is forced to retire (scenario analysis, set null for no
+/// forced retirement)
+/// * `strategy_controller` - Strategy controller that decides about the pit stops during the race
+/// (set null for the fixed plan given by the strategy entries)
+/// * `tiresets_available` - Tire sets that are left from the tire allocation of the race weekend
+/// (set null if the allocation should not be checked)
+/// * `penalties` - List of penalties that are issued to the car during the race (scenario analysis,
+/// in addition to the randomly drawn penalties)
 #[derive(Debug, Deserialize, Clone)]
 pub struct CarPars {
     pub car_no: u32,
@@ -49,13 +66,32 @@ pub struct CarPars {
     pub pit_location: f64,
     pub strategy: Vec<StrategyEntry>,
     pub p_grid: u32,
+    #[serde(default)]
+    pub grid_penalty: Option<GridPenalty>,
+    #[serde(default)]
+    pub p_failure: Option<f64>,
+    #[serde(default)]
+    pub dnf_lap: Option<u32>,
+    #[serde(default)]
+    pub strategy_controller: Option<StrategyControllerPars>,
+    #[serde(default)]
+    pub tiresets_available: Option<Vec<TiresetPars>>,
+    #[serde(default)]
+    pub penalties: Vec<PenaltyEntry>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GridPenalty {
+    Places(u32),  // car is moved back by the given number of grid positions
+    BackOfGrid,   // car starts from the back of the grid
+    PitlaneStart, // car starts from the pit lane after the field passed the pit exit
 }
 
 #[derive(Debug)]
 pub struct Car {
     pub car_no: u32,
-    team: String,
-    manufacturer: String,
+    pub team: String,
     pub color: String,
     t_car: f64,
     m_fuel: f64,
@@ -66,6 +102,15 @@ pub struct Car {
     pub pit_location: f64,
     strategy: Vec<StrategyEntry>,
     pub p_grid: u32,
+    pub grid_penalty: Option<GridPenalty>,
+    pub p_failure: Option<f64>,
+    pub dnf_lap: Option<u32>,
+    strategy_controller: Box<dyn StrategyController>,
+    pub tiresets_available: Option<Vec<TiresetPars>>,
+    tiresets_used: Vec<TiresetPars>,
+    last_inlap: Option<u32>,
+    penalty_entries: Vec<PenaltyEntry>,
+    penalties: Vec<Penalty>,
     pub driver: Rc<Driver>,
     pub sh: StateHandler,
     tireset: Tireset,
@@ -76,7 +121,6 @@ impl Car {
         Car {
             car_no: car_pars.car_no,
             team: car_pars.team.to_owned(),
-            manufacturer: car_pars.manufacturer.to_owned(),
             color: car_pars.color.to_owned(),
             t_car: car_pars.t_car,
             m_fuel: car_pars.m_fuel,
@@ -87,6 +131,21 @@ impl Car {
             pit_location: car_pars.pit_location,
             strategy: car_pars.strategy.to_owned(),
             p_grid: car_pars.p_grid,
+            grid_penalty: car_pars.grid_penalty.to_owned(),
+            p_failure: car_pars.p_failure,
+            dnf_lap: car_pars.dnf_lap,
+            strategy_controller: create_strategy_controller(
+                &car_pars.strategy_controller,
+                &car_pars.strategy,
+            ),
+            tiresets_available: car_pars.tiresets_available.to_owned(),
+            tiresets_used: vec![TiresetPars {
+                compound: car_pars.strategy[0].compound.to_owned(),
+                age: car_pars.strategy[0].tire_start_age,
+            }],
+            last_inlap: None,
+            penalty_entries: car_pars.penalties.to_owned(),
+            penalties: vec![],
             driver,
             sh: StateHandler::default(),
             tireset: Tireset::new(
@@ -106,6 +165,146 @@ impl Car {
             + self.m_fuel * s_mass
     }
 
+    /// The method returns the tire compound that is currently mounted on the car.
+    pub fn get_compound(&self) -> &str {
+        &self.tireset.compound
+    }
+
+    /// The method returns the total age of the tires that are currently mounted on the car.
+    pub fn get_tire_age(&self) -> u32 {
+        self.tireset.age_tot
+    }
+
+    /// The method returns the accumulated overheating of the tires that are currently mounted on
+    /// the car.
+    pub fn get_tire_overheating(&self) -> f64 {
+        self.tireset.overheating
+    }
+
+    /// The method adds the inserted overheating (caused by a lap on a too dry track) to the tires
+    /// that are currently mounted on the car.
+    pub fn add_tire_overheating(&mut self, overheating: f64) {
+        self.tireset.overheating += overheating
+    }
+
+    /// The method adds the inserted additional tire age (caused by driving in dirty air) to the
+    /// tires that are currently mounted on the car.
+    pub fn add_tire_age_dirty_air(&mut self, age: f64) {
+        self.tireset.age_dirty_air += age
+    }
+
+    /// The method returns the compounds that were used so far during the race.
+    pub fn get_compounds_used(&self) -> Vec<String> {
+        self.tiresets_used
+            .iter()
+            .map(|x| x.compound.to_owned())
+            .collect()
+    }
+
+    /// The method returns the tire sets that were used so far during the race (with their ages
+    /// when they were fitted).
+    pub fn get_tiresets_used(&self) -> &[TiresetPars] {
+        &self.tiresets_used
+    }
+
+    /// The method returns the in-lap of the last pit stop (None if the car did not pit yet).
+    pub fn get_last_inlap(&self) -> Option<u32> {
+        self.last_inlap
+    }
+
+    /// The method issues the penalties that are scheduled for the inserted lap to the car and
+    /// returns their types.
+    pub fn issue_scheduled_penalties(&mut self, lap: u32) -> Vec<PenaltyType> {
+        let penalty_types: Vec<PenaltyType> = self
+            .penalty_entries
+            .iter()
+            .filter(|x| x.lap == lap)
+            .map(|x| x.penalty_type)
+            .collect();
+
+        for &penalty_type in penalty_types.iter() {
+            self.add_penalty(penalty_type, lap)
+        }
+
+        penalty_types
+    }
+
+    /// The method issues a penalty of the inserted type to the car in the inserted lap.
+    pub fn add_penalty(&mut self, penalty_type: PenaltyType, lap: u32) {
+        self.penalties.push(Penalty {
+            car_no: self.car_no,
+            penalty_type,
+            lap_issued: lap,
+            lap_served: None,
+            t_added: 0.0,
+        })
+    }
+
+    /// The method returns the penalties that were issued to the car so far.
+    pub fn get_penalties(&self) -> &[Penalty] {
+        &self.penalties
+    }
+
+    /// The method checks if the car must visit the pit lane to serve a drive-through or
+    /// stop-and-go penalty.
+    pub fn get_pit_penalty_pending(&self) -> bool {
+        self.penalties
+            .iter()
+            .any(|x| x.lap_served.is_none() && x.penalty_type.requires_pit_visit())
+    }
+
+    /// The method checks if the pit lane visit in the inserted in-lap is a drive-through, i.e. it
+    /// does not belong to a pit stop of the strategy and a drive-through penalty is served.
+    pub fn get_drive_through(&self, inlap: u32) -> bool {
+        !self.strategy.iter().any(|x| x.inlap == inlap)
+            && self.get_next_pit_penalty().map(|x| x.penalty_type)
+                == Some(PenaltyType::DriveThrough)
+    }
+
+    /// The method returns the pending penalty that is served during the next pit lane visit that
+    /// does not belong to a pit stop of the strategy.
+    fn get_next_pit_penalty(&self) -> Option<&Penalty> {
+        self.penalties
+            .iter()
+            .find(|x| x.lap_served.is_none() && x.penalty_type.requires_pit_visit())
+    }
+
+    /// The method marks the penalties as served that are served in the pit lane visit of the
+    /// inserted in-lap. During a pit stop of the strategy, all pending time penalties are served.
+    /// A separate pit lane visit serves the next pending drive-through or stop-and-go penalty.
+    pub fn serve_penalties(&mut self, inlap: u32) {
+        if self.strategy.iter().any(|x| x.inlap == inlap) {
+            for penalty in self.penalties.iter_mut() {
+                if penalty.lap_served.is_none() && !penalty.penalty_type.requires_pit_visit() {
+                    penalty.lap_served = Some(inlap)
+                }
+            }
+        } else if let Some(penalty) = self
+            .penalties
+            .iter_mut()
+            .find(|x| x.lap_served.is_none() && x.penalty_type.requires_pit_visit())
+        {
+            penalty.lap_served = Some(inlap)
+        }
+    }
+
+    /// The method returns the standstill time required to change tires during a pit stop.
+    pub fn get_t_pit_tirechange(&self) -> f64 {
+        self.t_pit_tirechange
+    }
+
+    /// The method consults the strategy controller whether the car pits in the current lap and
+    /// updates the strategy entries accordingly.
+    pub fn decide_pit(&mut self, strategy_info: &StrategyInfo) {
+        let strategy_entry = self.strategy_controller.decide_pit(strategy_info);
+
+        self.strategy.retain(|x| x.inlap != strategy_info.cur_lap);
+
+        if let Some(strategy_entry) = strategy_entry {
+            self.strategy.push(strategy_entry)
+        }
+    }
+
     /// The method increases the tire age (for degradation) and reduces the fuel mass (burned during
     /// the lap).
     pub fn drive_lap(&mut self) {
@@ -144,7 +343,12 @@ impl Car {
 
     /// The method checks which of the strategy entries belongs to the current in-lap and performs
     /// the according pit stop in terms of changing tires and refueling (whatever is applicable).
+    /// Nothing is done if the car only visited the pit lane to serve a penalty.
     pub fn perform_pitstop(&mut self, inlap: u32, drivers_list: &HashMap<String, Rc<Driver>>) {
+        if !self.strategy.iter().any(|x| x.inlap == inlap) {
+            return;
+        }
+
         // get strategy entry
         let strategy_entry = self.get_strategy_entry(inlap);
 
@@ -154,8 +358,14 @@ impl Car {
                 strategy_entry.compound.to_owned(),
                 strategy_entry.tire_start_age,
             );
+            self.tiresets_used.push(TiresetPars {
+                compound: strategy_entry.compound.to_owned(),
+                age: strategy_entry.tire_start_age,
+            });
         }
 
+        self.last_inlap = Some(inlap);
+
         // handle refueling
         if strategy_entry.refuel_mass > 0.0 {
             self.m_fuel += strategy_entry.refuel_mass
@@ -171,14 +381,44 @@ impl Car {
         }
     }
 
-    /// The method returns the standstill time during a pit stop.
-    pub fn t_add_pit_standstill(&self, inlap: u32) -> f64 {
+    /// The method determines whether tires are changed during the pit stop in the inserted in-lap.
+    pub fn changes_tires(&self, inlap: u32) -> bool {
+        self.strategy
+            .iter()
+            .any(|x| x.inlap == inlap && !x.compound.is_empty())
+    }
+
+    /// The method returns the standstill time during a pit stop on the basis of the inserted tire
+    /// change time. Pending time penalties are served before any work is done on the car. If the
+    /// car only visits the pit lane to serve a penalty, the standstill time of a stop-and-go
+    /// penalty is returned (zero for a drive-through).
+    pub fn t_add_pit_standstill(
+        &self,
+        inlap: u32,
+        t_pit_tirechange: f64,
+        penalty_pars: &PenaltyPars,
+    ) -> f64 {
+        if !self.strategy.iter().any(|x| x.inlap == inlap) {
+            return match self.get_next_pit_penalty().map(|x| x.penalty_type) {
+                Some(PenaltyType::StopAndGo) => penalty_pars.t_standstill_stop_and_go,
+                _ => 0.0,
+            };
+        }
+
+        // handle time penalties
+        let t_penalties: f64 = self
+            .penalties
+            .iter()
+            .filter(|x| x.lap_served.is_none() && !x.penalty_type.requires_pit_visit())
+            .map(|x| x.penalty_type.get_t_penalty(penalty_pars))
+            .sum();
+
         // get strategy entry
         let strategy_entry = self.get_strategy_entry(inlap);
 
         // handle tire change
         let mut t_add_pit_standstill = if !strategy_entry.compound.is_empty() {
-            self.t_pit_tirechange
+            t_pit_tirechange
         } else {
             0.0
         };
@@ -201,6 +441,6 @@ impl Car {
             )
         }
 
-        t_add_pit_standstill
+        t_penalties + t_add_pit_standstill
     }
 }
@@ -5,8 +5,15 @@ use std::collections::HashMap;
 /// * `initials` - Driver initials, e.g. BOT
 /// * `name` - Driver name, e.g. Valtteri Bottas
 /// * `t_driver` - (s) Time loss per lap due to driver abilities
-/// * `t_teamorder` - (s) Team order time delta (negative or positive)
+/// * `t_teamorder` - (s) Time loss of the driver when the driver lets the teammate pass due to a
+/// team order (must not be negative)
 /// * `vel_max` - (km/h) Maximum velocity during qualifying
+/// * `t_lap_var_sigma` - (s) Standard deviation of the random lap time variation (normal
+/// distribution, drawn once per lap)
+/// * `p_accident` - Probability of an accident per lap (overrides the track-specific probability in
+/// the track parameters if set)
+/// * `start_perf_pars` - Start performance of the driver (only used if the stochastic start model
+/// is activated, set null for an average start)
 /// * `degr_pars_all` - Map containing the degradation parameters for all relevant tire compounds
 #[derive(Debug, Deserialize, Clone)]
 pub struct DriverPars {
@@ -15,16 +22,37 @@ pub struct DriverPars {
     pub t_driver: f64,
     pub t_teamorder: f64,
     pub vel_max: f64,
+    #[serde(default)]
+    pub t_lap_var_sigma: f64,
+    #[serde(default)]
+    pub p_accident: Option<f64>,
+    #[serde(default)]
+    pub start_perf_pars: Option<StartPerfPars>,
     pub degr_pars_all: HashMap<String, DegrPars>,
 }
 
+/// * `t_reaction_mean` - (s) Mean reaction time at the race start
+/// * `t_reaction_sigma` - (s) Standard deviation of the reaction time (normal distribution)
+/// * `t_launch_mean` - (s) Mean time loss of the launch until turn 1 compared to an average launch
+/// (negative for a better launch)
+/// * `t_launch_sigma` - (s) Standard deviation of the launch time loss (normal distribution)
+#[derive(Debug, Deserialize, Clone)]
+pub struct StartPerfPars {
+    pub t_reaction_mean: f64,
+    pub t_reaction_sigma: f64,
+    pub t_launch_mean: f64,
+    pub t_launch_sigma: f64,
+}
+
 #[derive(Debug)]
 pub struct Driver {
     pub initials: String,
-    name: String,
     pub t_driver: f64,
-    t_teamorder: f64,
-    vel_max: f64,
+    pub t_teamorder: f64,
+    pub vel_max: f64,
+    pub t_lap_var_sigma: f64,
+    pub p_accident: Option<f64>,
+    pub start_perf_pars: Option<StartPerfPars>,
     degr_pars_all: HashMap<String, DegrPars>,
 }
 
@@ -32,14 +60,23 @@ impl Driver {
     pub fn new(driver_pars: &DriverPars) -> Driver {
         Driver {
             initials: driver_pars.initials.to_owned(),
-            name: driver_pars.name.to_owned(),
             t_driver: driver_pars.t_driver,
             t_teamorder: driver_pars.t_teamorder,
             vel_max: driver_pars.vel_max,
+            t_lap_var_sigma: driver_pars.t_lap_var_sigma,
+            p_accident: driver_pars.p_accident,
+            start_perf_pars: driver_pars.start_perf_pars.to_owned(),
             degr_pars_all: driver_pars.degr_pars_all.to_owned(),
         }
     }
 
+    /// The method returns the compounds for which degradation parameters are available (sorted).
+    pub fn get_compounds(&self) -> Vec<String> {
+        let mut compounds: Vec<String> = self.degr_pars_all.keys().cloned().collect();
+        compounds.sort();
+        compounds
+    }
+
     /// The method returns the degradation parameters of the current driver for the given compound.
     pub fn get_degr_pars(&self, compound: &str) -> DegrPars {
         self.degr_pars_all
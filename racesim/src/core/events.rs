@@ -0,0 +1,84 @@
+use crate::core::penalties::PenaltyType;
+use crate::core::race::FcyPhaseType;
+use serde::{Deserialize, Serialize};
+
+/// * `p_sc_accident` - Probability that an accident leads to a safety car phase (a virtual safety
+/// car phase otherwise)
+/// * `p_vsc_failure` - Probability that a technical failure leads to a virtual safety car phase (no
+/// FCY phase otherwise, e.g. if the car reaches the pits)
+/// * `sc_duration_laps` - Range (min, max) of the number of laps until the end of a safety car
+/// phase is announced (uniformly distributed)
+/// * `vsc_duration` - (s) Range (min, max) of the duration of a virtual safety car phase
+/// (uniformly distributed)
+#[derive(Debug, Deserialize, Clone)]
+pub struct EventPars {
+    pub p_sc_accident: f64,
+    pub p_vsc_failure: f64,
+    pub sc_duration_laps: [u32; 2],
+    pub vsc_duration: [f64; 2],
+}
+
+impl Default for EventPars {
+    fn default() -> Self {
+        EventPars {
+            p_sc_accident: 0.6,
+            p_vsc_failure: 0.3,
+            sc_duration_laps: [3, 6],
+            vsc_duration: [60.0, 180.0],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RaceEventKind {
+    Accident,
+    Failure,
+    Retirement,
+    FcyStart(FcyPhaseType),
+    FcyEnd(FcyPhaseType),
+    TeamOrderSwap(u32), // car number of the teammate that is let through
+    Penalty(PenaltyType),
+    StartIncident, // incident in the first corner that the car survives with a time loss
+    UnsafeRelease, // car was released from its pit box into the path of a car in the fast lane
+    Overtake(u32), // car number of the car that was overtaken on the track
+    DrsActivation, // DRS is activated when entering an overtaking zone
+    PitEntry,
+    PitExit,
+    StandstillStart, // car stops in its pit box
+    StandstillEnd,   // car leaves its pit box (or the end of the pit lane after a pit lane start)
+    ChequeredFlag,   // leader crosses the finish line at the end of the race
+    Finish,          // car crosses the finish line after the chequered flag
+}
+
+impl RaceEventKind {
+    /// get_on_track returns true for the frequent events that happen on the track during the race
+    /// (overtakes, DRS activations, pit lane and standstill events). They are only printed in the
+    /// complete race event log.
+    pub fn get_on_track(&self) -> bool {
+        matches!(
+            self,
+            RaceEventKind::Overtake(_)
+                | RaceEventKind::DrsActivation
+                | RaceEventKind::PitEntry
+                | RaceEventKind::PitExit
+                | RaceEventKind::StandstillStart
+                | RaceEventKind::StandstillEnd
+        )
+    }
+}
+
+/// RaceEvent is used to record important events during the race.
+///
+/// * `t_race` - (s) Race time at which the event happens
+/// * `lap` - Lap in which the event happens (lap of the car for car-specific events, lap of the
+/// leader otherwise)
+/// * `car_no` - Car number of the involved car (None for events that affect the whole race)
+/// * `kind` - Kind of the event
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RaceEvent {
+    pub t_race: f64,
+    pub lap: u32,
+    pub car_no: Option<u32>,
+    pub kind: RaceEventKind,
+}
@@ -1,4 +1,5 @@
 use crate::core::race::Race;
+use crate::core::telemetry::TelemetryOpts;
 use crate::interfaces::gui_interface::{CarState, RaceState, RgbColor, MAX_GUI_UPDATE_FREQUENCY};
 use crate::post::race_result::RaceResult;
 use crate::pre::read_sim_pars::SimPars;
@@ -9,13 +10,16 @@ use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 /// handle_race creates and simulates a race on the basis of the inserted parameters, and returns
-/// the results for post-processing.
+/// the results for post-processing. The seed initializes the random number generator of the race
+/// such that a simulation run can be replayed exactly. If telemetry options are inserted, the
+/// telemetry of the race is recorded and written to the given file.
 pub fn handle_race(
     sim_pars: &SimPars,
     timestep_size: f64,
-    print_debug: bool,
+    seed: u64,
     tx: Option<&Sender<RaceState>>,
     realtime_factor: f64,
+    telemetry_opts: Option<&TelemetryOpts>,
 ) -> anyhow::Result<RaceResult> {
     // create the race
     let mut race = Race::new(
@@ -24,8 +28,14 @@ pub fn handle_race(
         &sim_pars.driver_pars_all,
         &sim_pars.car_pars_all,
         timestep_size,
+        seed,
     );
 
+    // activate telemetry recording if indicated
+    if let Some(telemetry_opts) = telemetry_opts {
+        race.activate_telemetry(telemetry_opts.decimation);
+    }
+
     // check if sender was inserted -> in that case use real-time simulation for GUI
     let sim_realtime = tx.is_some();
 
@@ -62,9 +72,18 @@ pub fn handle_race(
                 let mut race_state = RaceState {
                     car_states: Vec::with_capacity(race.cars_list.len()),
                     flag_state: race.flag_state.to_owned(),
+                    sc_race_prog: race
+                        .safety_car
+                        .as_ref()
+                        .map(|safety_car| safety_car.get_lap_frac()),
                 };
 
-                for car in race.cars_list.iter() {
+                for (i, car) in race.cars_list.iter().enumerate() {
+                    // retired cars are not shown anymore
+                    if race.get_retired(i) {
+                        continue;
+                    }
+
                     // convert hex color to a rgb color
                     let tmp_color = car
                         .color
@@ -102,12 +121,9 @@ pub fn handle_race(
         }
     }
 
-    // print debug information if indicated
-    if print_debug {
-        println!(
-            "DEBUG: Estimated time loss for driving through the pit lane (w/o standstill): {:.2}s",
-            race.track.get_pit_drive_timeloss()
-        )
+    // write telemetry to a file if indicated
+    if let (Some(telemetry_opts), Some(telemetry)) = (telemetry_opts, race.get_telemetry()) {
+        telemetry.write_json(telemetry_opts.path.as_path())?;
     }
 
     // return race result
@@ -1,7 +1,15 @@
 pub mod car;
 pub mod driver;
+pub mod events;
 pub mod handle_race;
+pub mod penalties;
+pub mod pit_stops;
 pub mod race;
+pub mod regulations;
+pub mod safety_car;
 pub mod state_handler;
+pub mod strategy_controller;
+pub mod telemetry;
 pub mod tireset;
 pub mod track;
+pub mod weather;
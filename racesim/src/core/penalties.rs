@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PenaltyType {
+    Time5,        // 5s time penalty, served at the next pit stop (before any work on the car)
+    Time10,       // 10s time penalty, served at the next pit stop (before any work on the car)
+    DriveThrough, // drive through the pit lane without stopping
+    StopAndGo,    // stop in the pit lane without any work on the car
+}
+
+/// Penalty types in the order of the probabilities in PenaltyPars.
+pub const PENALTY_TYPES: [PenaltyType; 4] = [
+    PenaltyType::Time5,
+    PenaltyType::Time10,
+    PenaltyType::DriveThrough,
+    PenaltyType::StopAndGo,
+];
+
+impl PenaltyType {
+    /// The method checks if the penalty must be served by a separate visit of the pit lane.
+    pub fn requires_pit_visit(&self) -> bool {
+        matches!(self, PenaltyType::DriveThrough | PenaltyType::StopAndGo)
+    }
+
+    /// The method returns the time of the penalty, i.e. the standstill time of a time penalty that
+    /// is served at a pit stop, or the time that is added to the final race time if the penalty was
+    /// not served during the race.
+    pub fn get_t_penalty(&self, penalty_pars: &PenaltyPars) -> f64 {
+        match self {
+            PenaltyType::Time5 => 5.0,
+            PenaltyType::Time10 => 10.0,
+            PenaltyType::DriveThrough => penalty_pars.t_drive_through_unserved,
+            PenaltyType::StopAndGo => penalty_pars.t_stop_and_go_unserved,
+        }
+    }
+}
+
+/// * `p_penalty` - Probability of a penalty per car and lap
+/// * `p_penalty_types` - Probabilities of the penalty types (5s, 10s, drive-through, stop-and-go)
+/// if a penalty is drawn (must sum up to 1.0)
+/// * `t_standstill_stop_and_go` - (s) Standstill time of a stop-and-go penalty
+/// * `t_drive_through_unserved` - (s) Time that is added to the final race time if a drive-through
+/// penalty could not be served before the end of the race
+/// * `t_stop_and_go_unserved` - (s) Time that is added to the final race time if a stop-and-go
+/// penalty could not be served before the end of the race
+#[derive(Debug, Deserialize, Clone)]
+pub struct PenaltyPars {
+    pub p_penalty: f64,
+    pub p_penalty_types: [f64; 4],
+    pub t_standstill_stop_and_go: f64,
+    pub t_drive_through_unserved: f64,
+    pub t_stop_and_go_unserved: f64,
+}
+
+impl Default for PenaltyPars {
+    fn default() -> Self {
+        PenaltyPars {
+            p_penalty: 0.0,
+            p_penalty_types: [0.5, 0.3, 0.15, 0.05],
+            t_standstill_stop_and_go: 10.0,
+            t_drive_through_unserved: 20.0,
+            t_stop_and_go_unserved: 30.0,
+        }
+    }
+}
+
+/// * `lap` - Lap at whose start the penalty is issued
+/// * `penalty_type` - Type of the penalty
+#[derive(Debug, Deserialize, Clone)]
+pub struct PenaltyEntry {
+    pub lap: u32,
+    pub penalty_type: PenaltyType,
+}
+
+/// Penalty is used to track a penalty of a car during the race and to record it in the race
+/// result.
+///
+/// * `car_no` - Car number of the penalized car
+/// * `penalty_type` - Type of the penalty
+/// * `lap_issued` - Lap in which the penalty was issued
+/// * `lap_served` - In-lap of the pit stop or pit lane visit in which the penalty was served (None
+/// if it was not served during the race)
+/// * `t_added` - (s) Time that was added to the final race time since the penalty was not served
+/// during the race
+#[derive(Debug, Clone, Serialize)]
+pub struct Penalty {
+    pub car_no: u32,
+    pub penalty_type: PenaltyType,
+    pub lap_issued: u32,
+    pub lap_served: Option<u32>,
+    pub t_added: f64,
+}
@@ -0,0 +1,136 @@
+use crate::core::penalties::PenaltyType;
+use rand::Rng;
+use rand_distr::{Distribution, Normal, Pareto};
+use serde::{Deserialize, Serialize};
+
+/// * `t_tirechange_sigma` - (s) Standard deviation of the tire change time around the car's
+/// t_pit_tirechange (normal distribution)
+/// * `p_slow_stop` - Probability of a slow stop per pit stop (e.g. due to a wheel gun failure)
+/// * `t_slow_stop_min` - (s) Minimum additional standstill time of a slow stop
+/// * `k_slow_stop` - Shape of the Pareto distribution of the additional standstill time of a slow
+/// stop (must be greater than 1.0, the smaller the value the heavier the tail)
+#[derive(Debug, Deserialize, Clone)]
+pub struct PitStopPars {
+    pub t_tirechange_sigma: f64,
+    pub p_slow_stop: f64,
+    pub t_slow_stop_min: f64,
+    pub k_slow_stop: f64,
+}
+
+impl PitStopPars {
+    /// The method draws the tire change time of a pit stop on the basis of the inserted mean tire
+    /// change time. It returns the tire change time and whether the stop was a slow stop.
+    pub fn draw_t_tirechange<R: Rng>(&self, t_tirechange_mean: f64, rng: &mut R) -> (f64, bool) {
+        let mut t_tirechange = Normal::new(t_tirechange_mean, self.t_tirechange_sigma)
+            .expect("Invalid standard deviation of the tire change time!")
+            .sample(rng)
+            .max(0.0);
+
+        let slow_stop = rng.gen_bool(self.p_slow_stop);
+
+        if slow_stop {
+            t_tirechange += Pareto::new(self.t_slow_stop_min, self.k_slow_stop)
+                .expect("Invalid parameters of the slow stop time distribution!")
+                .sample(rng);
+        }
+
+        (t_tirechange, slow_stop)
+    }
+}
+
+/// * `t_release_gap` - (s) Minimum gap to a car in the fast lane that is approaching the pit box
+/// which is required for a safe release (the car is held in its box otherwise)
+/// * `p_unsafe_release` - Probability that a team releases its car into the path of a car in the
+/// fast lane instead of holding it in its box
+/// * `penalty_unsafe_release` - Penalty that is issued for an unsafe release
+#[derive(Debug, Deserialize, Clone)]
+pub struct PitLanePars {
+    pub t_release_gap: f64,
+    pub p_unsafe_release: f64,
+    pub penalty_unsafe_release: PenaltyType,
+}
+
+/// PitStop is used to record a pit stop in the race result.
+///
+/// * `car_no` - Car number
+/// * `inlap` - In-lap of the pit stop
+/// * `t_race` - (s) Race time at which the standstill started
+/// * `t_tirechange` - (s) Drawn tire change time (zero if no tires were changed)
+/// * `t_standstill` - (s) Total standstill time (including refueling, driver change, time penalties
+/// served during the stop, and queueing delays)
+/// * `slow_stop` - True if the pit stop was a slow stop
+/// * `t_queue` - (s) Time the car waited for its teammate to leave the pit box (double stacking)
+/// * `t_release_hold` - (s) Time the car was held in its pit box to let cars in the fast lane pass
+/// * `unsafe_release` - True if the car was released into the path of a car in the fast lane
+#[derive(Debug, Clone, Serialize)]
+pub struct PitStop {
+    pub car_no: u32,
+    pub inlap: u32,
+    pub t_race: f64,
+    pub t_tirechange: f64,
+    pub t_standstill: f64,
+    pub slow_stop: bool,
+    pub t_queue: f64,
+    pub t_release_hold: f64,
+    pub unsafe_release: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PitStopPars;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn create_pit_stop_pars(p_slow_stop: f64) -> PitStopPars {
+        PitStopPars {
+            t_tirechange_sigma: 0.0,
+            p_slow_stop,
+            t_slow_stop_min: 1.5,
+            k_slow_stop: 1.8,
+        }
+    }
+
+    #[test]
+    fn test_draw_t_tirechange_no_slow_stops() {
+        let pit_stop_pars = create_pit_stop_pars(0.0);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            assert_eq!(pit_stop_pars.draw_t_tirechange(2.5, &mut rng), (2.5, false));
+        }
+    }
+    #[test]
+    fn test_draw_t_tirechange_not_negative() {
+        let mut pit_stop_pars = create_pit_stop_pars(0.0);
+        pit_stop_pars.t_tirechange_sigma = 1.0;
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            assert!(pit_stop_pars.draw_t_tirechange(0.5, &mut rng).0 >= 0.0);
+        }
+    }
+    #[test]
+    fn test_draw_t_tirechange_slow_stop_tail() {
+        // the additional standstill time of a slow stop is at least t_slow_stop_min and exceeds
+        // twice this value with a probability of 2^-k_slow_stop (Pareto distribution)
+        let pit_stop_pars = create_pit_stop_pars(1.0);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let no_draws = 10000;
+        let mut no_exceeded = 0;
+
+        for _ in 0..no_draws {
+            let (t_tirechange, slow_stop) = pit_stop_pars.draw_t_tirechange(2.5, &mut rng);
+            let t_slow_stop = t_tirechange - 2.5;
+
+            assert!(slow_stop);
+            assert!(t_slow_stop >= pit_stop_pars.t_slow_stop_min);
+
+            if t_slow_stop > 2.0 * pit_stop_pars.t_slow_stop_min {
+                no_exceeded += 1;
+            }
+        }
+
+        let p_exceeded = no_exceeded as f64 / no_draws as f64;
+        assert!((p_exceeded - 2.0_f64.powf(-pit_stop_pars.k_slow_stop)).abs() < 0.02);
+    }
+}
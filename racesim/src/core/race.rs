@@ -1,22 +1,80 @@
-use crate::core::car::{Car, CarPars};
+use crate::core::car::{Car, CarPars, GridPenalty};
 use crate::core::driver::{Driver, DriverPars};
+use crate::core::events::{EventPars, RaceEvent, RaceEventKind};
+use crate::core::penalties::{PenaltyPars, PENALTY_TYPES};
+use crate::core::pit_stops::{PitLanePars, PitStop, PitStopPars};
+use crate::core::regulations::{Enforcement, RegulationPars, RegulationViolation, Regulations};
+use crate::core::safety_car::SafetyCar;
+use crate::core::strategy_controller::{RivalInfo, StrategyInfo};
+use crate::core::telemetry::Telemetry;
 use crate::core::track::{Track, TrackPars};
+use crate::core::weather::{Weather, WeatherPars};
 use crate::post::race_result::{CarDriverPair, RaceResult};
-use helpers::general::{argmax, argsort, SortOrder};
-use serde::Deserialize;
+use helpers::general::{argmax, argmin, argsort, SortOrder};
+use rand::distributions::WeightedIndex;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Margin by which the minimum lap time of the cars behind the safety car is below the lap time of
+/// the safety car (relative to the race lap time), i.e. the cars can drive at (f_laptime_sc -
+/// F_LAPTIME_SC_CATCH_UP) times the race lap time to catch up with the end of the safety car queue.
+/// f_laptime_sc must therefore be greater than 1.0 + F_LAPTIME_SC_CATCH_UP such that the cars never
+/// drive faster than at race pace.
+pub const F_LAPTIME_SC_CATCH_UP: f64 = 0.2;
+
 /// * `season` - Season of the race
 /// * `tot_no_laps` - Total number of laps in the race
 /// * `drs_allowed_lap` - DRS activation is allowed from this lap onwards (usually second lap)
 /// * `min_t_dist` - (s) Minimal temporal distance to driver in front
 /// * `t_duel` - (s) Time loss applied to duelling drivers if they fight for position
 /// * `t_overtake_loser` - (s) Time loss applied to the loser of an overtaking maneuver
+/// * `t_dirty_air` - (s) Lap time loss when following directly behind another car for a whole lap
+/// (decreases linearly to zero at dirty_air_window, applied outside overtaking zones)
+/// * `dirty_air_window` - (s) Gap to the car in front up to which a car is affected by dirty air
+/// (zero for no dirty air)
+/// * `f_degr_dirty_air` - (laps) Additional tire age per lap driven directly behind another car
+/// (scaled like the lap time loss)
 /// * `drs_window` - (s) DRS window, usually 1.0s
 /// * `use_drs` - Boolean to determine whether DRS is used in the race
 /// * `participants` - List of participants (car numbers) in the current race (the respective car
 /// parameters must be available)
+/// * `min_t_dist_sc` - (s) Minimal temporal distance to the car (or safety car) in front during a
+/// safety car phase
+/// * `f_laptime_sc` - Lap time of the safety car relative to the race lap time (t_q +
+/// t_gap_racepace, must be greater than 1.0 + F_LAPTIME_SC_CATCH_UP)
+/// * `f_laptime_vsc` - Lap time of the virtual safety car delta relative to the race lap time (t_q
+/// + t_gap_racepace)
+/// * `t_vsc_ending` - (s) Duration of the VSC ending phase (delta must still be kept, green flag
+/// afterward)
+/// * `fcy_phases` - List of full course yellow phases in the race
+/// * `p_failure` - Probability of a technical failure per car and lap (season-specific)
+/// * `event_pars` - Parameters required to translate accidents and failures into FCY phases
+/// * `regulation_pars` - Parameters that determine how violations of the (season-specific)
+/// regulations are handled
+/// * `penalty_pars` - Parameters of the penalties that are issued during the race
+/// * `weather_pars` - Track wetness over the race time and wetness parameters of the compounds
+/// (set null for a dry race)
+/// * `team_order_pars` - Rules according to which the teams swap the positions of their cars (set
+/// null for no team orders)
+/// * `blue_flag_pars` - Rules according to which lapped cars must let the lapping cars through (set
+/// null for no blue flags)
+/// * `start_pars` - Parameters of the stochastic race start model (set null for a deterministic
+/// race start, i.e. every car loses t_loss_firstlap until turn 1)
+/// * `pit_stop_pars` - Map containing the pit stop time distributions of the teams (the tire
+/// change time of the cars of a team without an entry is always t_pit_tirechange)
+/// * `pit_lane_pars` - Parameters of the interaction between the cars in the pit lane, i.e. double
+/// stacking, release checks, and order in the fast lane (set null to let the cars in the pit lane
+/// ignore each other)
+///
+/// The dirty air, FCY, failure, regulation, penalty, weather, team order, blue flag, start, and pit
+/// parameters are optional such that older parameter files can still be read. If they are missing,
+/// the respective feature is switched off (regulations are not checked). The SC and VSC parameters
+/// default to min_t_dist_sc = 1.0s, f_laptime_sc = 1.6, f_laptime_vsc = 1.4, and t_vsc_ending =
+/// 10.0s in that case.
 #[derive(Debug, Deserialize, Clone)]
 pub struct RacePars {
     pub season: u32,
@@ -25,18 +83,161 @@ pub struct RacePars {
     pub min_t_dist: f64,
     pub t_duel: f64,
     pub t_overtake_loser: f64,
+    #[serde(default)]
+    pub t_dirty_air: f64,
+    #[serde(default)]
+    pub dirty_air_window: f64,
+    #[serde(default)]
+    pub f_degr_dirty_air: f64,
     pub drs_window: f64,
     pub use_drs: bool,
     pub participants: Vec<u32>,
+    #[serde(default = "default_min_t_dist_sc")]
+    pub min_t_dist_sc: f64,
+    #[serde(default = "default_f_laptime_sc")]
+    pub f_laptime_sc: f64,
+    #[serde(default = "default_f_laptime_vsc")]
+    pub f_laptime_vsc: f64,
+    #[serde(default = "default_t_vsc_ending")]
+    pub t_vsc_ending: f64,
+    #[serde(default)]
+    pub fcy_phases: Vec<FcyPhase>,
+    #[serde(default)]
+    pub p_failure: f64,
+    #[serde(default)]
+    pub event_pars: EventPars,
+    #[serde(default)]
+    pub regulation_pars: RegulationPars,
+    #[serde(default)]
+    pub penalty_pars: PenaltyPars,
+    #[serde(default)]
+    pub weather_pars: Option<WeatherPars>,
+    #[serde(default)]
+    pub team_order_pars: Option<TeamOrderPars>,
+    #[serde(default)]
+    pub blue_flag_pars: Option<BlueFlagPars>,
+    #[serde(default)]
+    pub start_pars: Option<StartPars>,
+    #[serde(default)]
+    pub pit_stop_pars: HashMap<String, PitStopPars>,
+    #[serde(default)]
+    pub pit_lane_pars: Option<PitLanePars>,
+}
+
+fn default_min_t_dist_sc() -> f64 {
+    1.0
+}
+
+fn default_f_laptime_sc() -> f64 {
+    1.6
+}
+
+fn default_f_laptime_vsc() -> f64 {
+    1.4
+}
+
+fn default_t_vsc_ending() -> f64 {
+    10.0
+}
+
+/// * `t_pace_delta_min` - (s) Minimum pace advantage (car, driver, tires, fuel) of the trailing car
+/// over its teammate in front that is required to let it through
+/// * `max_gap` - (s) Maximum gap between the teammates up to which team orders are given (nose to
+/// tail)
+/// * `lap_window` - Range (first, last) of the laps in which team orders are given
+/// * `hold_position` - True if the trailing car must hold its position behind its teammate if it
+/// is not let through, i.e. it is not allowed to attack it
+#[derive(Debug, Deserialize, Clone)]
+pub struct TeamOrderPars {
+    pub t_pace_delta_min: f64,
+    pub max_gap: f64,
+    pub lap_window: [u32; 2],
+    pub hold_position: bool,
+}
+
+/// * `blue_flag_window` - (s) Gap of a lapping car to a lapped car in front up to which blue flags
+/// are shown to the lapped car
+/// * `s_yield` - (m) Distance within which a lapped car must let the lapping car through after the
+/// blue flags were shown (the lapping car is stuck behind it until then)
+/// * `t_yield` - (s) Time loss of a lapped car when it lets a lapping car through
+/// * `unlap_behind_sc` - True if lapped cars are allowed to unlap themselves when the end of a
+/// safety car phase is announced
+#[derive(Debug, Deserialize, Clone)]
+pub struct BlueFlagPars {
+    pub blue_flag_window: f64,
+    pub s_yield: f64,
+    pub t_yield: f64,
+    pub unlap_behind_sc: bool,
+}
+
+/// * `t_loss_dirty_side` - (s) Time loss until turn 1 of the cars that start on the dirty side of
+/// the grid (off the racing line)
+/// * `pole_side_dirty` - True if the pole position (and every other odd grid position) is on the
+/// dirty side of the grid
+/// * `p_incident_turn_1` - Probability of an incident per car in the first corner
+/// * `t_loss_incident` - (s) Range (min, max) of the time loss of a car that is involved in an
+/// incident in the first corner (uniformly distributed)
+/// * `p_dnf_incident` - Probability that an incident in the first corner ends the race of the car
+/// (treated like an accident)
+#[derive(Debug, Deserialize, Clone)]
+pub struct StartPars {
+    pub t_loss_dirty_side: f64,
+    pub pole_side_dirty: bool,
+    pub p_incident_turn_1: f64,
+    pub t_loss_incident: [f64; 2],
+    pub p_dnf_incident: f64,
+}
+
+/// BlueFlag is shown to a lapped car when a lapping car approaches it. It contains the index of the
+/// lapping car, the race progress of the lapped car when the blue flag was shown, and whether the
+/// lapped car already lets the lapping car through.
+#[derive(Debug, Clone)]
+struct BlueFlag {
+    idx_lapping: usize,
+    race_prog_shown: f64,
+    yielded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Unlapping {
+    Passing(f64), // car passes the cars in front until its deficit to the leader is below x laps
+    CatchingUp,   // car catches up with the end of the field after passing the safety car
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamOrder {
+    Swap, // the car in front lets its teammate through
+    Hold, // the trailing car holds its position behind its teammate
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FcyPhaseType {
+    Sc,
+    Vsc,
+}
+
+/// * `phase_type` - Type of the full course yellow phase -> sc (safety car), vsc (virtual safety
+/// car)
+/// * `t_start` - (s) Race time at which the phase starts
+/// * `t_end` - (s) Race time at which the end of the phase is announced (SC: the safety car enters
+/// the pits at the end of the current lap, the race is restarted when the leader crosses the
+/// finish line afterward, VSC: the VSC ending phase starts)
+#[derive(Debug, Deserialize, Clone)]
+pub struct FcyPhase {
+    pub phase_type: FcyPhaseType,
+    pub t_start: f64,
+    pub t_end: f64,
 }
 
 #[derive(Debug, Clone)]
 pub enum FlagState {
-    G,   // green
-    Y,   // yellow
-    Vsc, // virtual safety car
-    Sc,  // safety car
-    C,   // chequered
+    G,         // green
+    Y,         // yellow
+    Vsc,       // virtual safety car
+    VscEnding, // virtual safety car ending
+    Sc,        // safety car
+    C,         // chequered
 }
 
 impl Default for FlagState {
@@ -48,23 +249,69 @@ impl Default for FlagState {
 #[derive(Debug)]
 pub struct Race {
     pub timestep_size: f64,
+    seed: u64,
+    rng: ChaCha8Rng,
     pub cur_racetime: f64,
-    season: u32,
     pub tot_no_laps: u32,
     drs_allowed_lap: u32,
     pub cur_lap_leader: u32,
     min_t_dist: f64,
     t_duel: f64,
     t_overtake_loser: f64,
+    t_dirty_air: f64,
+    dirty_air_window: f64,
+    f_degr_dirty_air: f64,
     drs_window: f64,
     use_drs: bool,
+    min_t_dist_sc: f64,
+    f_laptime_sc: f64,
+    f_laptime_vsc: f64,
+    t_vsc_ending: f64,
+    fcy_phases: Vec<FcyPhase>,
+    cur_fcy_phase: Option<FcyPhase>,
+    t_vsc_start: f64,
+    race_progs_vsc_start: Vec<f64>,
+    p_failure: f64,
+    event_pars: EventPars,
+    regulations: Rc<Regulations>,
+    regulation_pars: RegulationPars,
+    penalty_pars: PenaltyPars,
+    weather: Option<Rc<Weather>>,
+    evo_carlaps: f64,
+    team_order_pars: Option<TeamOrderPars>,
+    blue_flag_pars: Option<BlueFlagPars>,
+    start_pars: Option<StartPars>,
+    pit_stop_pars: HashMap<String, PitStopPars>,
+    pit_lane_pars: Option<PitLanePars>,
+    pit_stops: Vec<PitStop>,
+    pub race_events: Vec<RaceEvent>,
     pub flag_state: FlagState,
+    pub safety_car: Option<SafetyCar>,
     pub track: Track,
     race_finished: Vec<bool>,
+    t_retirements: Vec<Option<f64>>,
+    retirement_laps: Vec<Option<u32>>,
     pub laptimes: Vec<Vec<f64>>,
     pub racetimes: Vec<Vec<f64>>,
+    compounds: Vec<Vec<String>>,
+    sectortimes: Vec<Vec<[f64; 3]>>,
+    drs_point_times: Vec<Vec<Vec<f64>>>,
+    t_sector_bounds: Vec<[f64; 2]>,
     cur_laptimes: Vec<f64>,
     cur_th_laptimes: Vec<f64>,
+    delta_ts_front: Vec<f64>,
+    idxs_front: Vec<Option<usize>>,
+    overtaking_attempts: Vec<Option<usize>>,
+    overtaking_targets: Vec<Option<usize>>,
+    team_order_swaps: Vec<Option<usize>>,
+    blue_flags: Vec<Option<BlueFlag>>,
+    unlapping: Vec<Option<Unlapping>>,
+    race_progs_prev: Vec<f64>,
+    t_overtake_losses: Vec<f64>,
+    pitlane_starts: Vec<bool>,
+    t_start_losses: Vec<f64>,
+    release_holds: Vec<bool>,
+    telemetry: Option<Telemetry>,
     pub cars_list: Vec<Car>,
     drivers_list: HashMap<String, Rc<Driver>>,
 }
@@ -76,6 +323,7 @@ impl Race {
         driver_pars_all: &HashMap<String, DriverPars>,
         car_pars_all: &HashMap<u32, CarPars>,
         timestep_size: f64,
+        seed: u64,
     ) -> Race {
         // create drivers
         let mut drivers_list = HashMap::with_capacity(driver_pars_all.len());
@@ -106,40 +354,119 @@ impl Race {
         // sort cars list by car number
         cars_list.sort_unstable_by(|a, b| a.car_no.partial_cmp(&b.car_no).unwrap());
 
+        // sort FCY phases by their start times such that they can be activated one after another
+        let mut fcy_phases = race_pars.fcy_phases.to_owned();
+        fcy_phases.sort_by(|a, b| a.t_start.partial_cmp(&b.t_start).unwrap());
+
         // create race
         let mut race = Race {
             timestep_size,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
             cur_racetime: 0.0,
-            season: race_pars.season,
             tot_no_laps: race_pars.tot_no_laps,
             drs_allowed_lap: race_pars.drs_allowed_lap,
             cur_lap_leader: 1,
             min_t_dist: race_pars.min_t_dist,
             t_duel: race_pars.t_duel,
             t_overtake_loser: race_pars.t_overtake_loser,
+            t_dirty_air: race_pars.t_dirty_air,
+            dirty_air_window: race_pars.dirty_air_window,
+            f_degr_dirty_air: race_pars.f_degr_dirty_air,
             drs_window: race_pars.drs_window,
             use_drs: race_pars.use_drs,
+            min_t_dist_sc: race_pars.min_t_dist_sc,
+            f_laptime_sc: race_pars.f_laptime_sc,
+            f_laptime_vsc: race_pars.f_laptime_vsc,
+            t_vsc_ending: race_pars.t_vsc_ending,
+            fcy_phases,
+            cur_fcy_phase: None,
+            t_vsc_start: 0.0,
+            race_progs_vsc_start: vec![0.0; no_cars],
+            p_failure: race_pars.p_failure,
+            event_pars: race_pars.event_pars.to_owned(),
+            regulations: Rc::new(Regulations::new(race_pars.season)),
+            regulation_pars: race_pars.regulation_pars.to_owned(),
+            penalty_pars: race_pars.penalty_pars.to_owned(),
+            weather: race_pars
+                .weather_pars
+                .as_ref()
+                .map(|weather_pars| Rc::new(Weather::new(weather_pars))),
+            evo_carlaps: 0.0,
+            team_order_pars: race_pars.team_order_pars.to_owned(),
+            blue_flag_pars: race_pars.blue_flag_pars.to_owned(),
+            start_pars: race_pars.start_pars.to_owned(),
+            pit_stop_pars: race_pars.pit_stop_pars.to_owned(),
+            pit_lane_pars: race_pars.pit_lane_pars.to_owned(),
+            pit_stops: vec![],
+            race_events: vec![],
             flag_state: FlagState::G,
+            safety_car: None,
             track: Track::new(track_pars),
             race_finished: vec![false; no_cars],
+            t_retirements: vec![None; no_cars],
+            retirement_laps: vec![None; no_cars],
             laptimes: vec![vec![0.0; race_pars.tot_no_laps as usize + 1]; no_cars],
             racetimes: vec![vec![0.0; race_pars.tot_no_laps as usize + 1]; no_cars],
+            compounds: vec![vec![String::new(); race_pars.tot_no_laps as usize + 1]; no_cars],
+            sectortimes: vec![vec![[0.0; 3]; race_pars.tot_no_laps as usize + 1]; no_cars],
+            drs_point_times: vec![
+                vec![
+                    vec![0.0; track_pars.drs_measurement_points.len()];
+                    race_pars.tot_no_laps as usize + 1
+                ];
+                no_cars
+            ],
+            t_sector_bounds: vec![[0.0; 2]; no_cars],
             cur_laptimes: vec![0.0; no_cars],
             cur_th_laptimes: vec![0.0; no_cars],
+            delta_ts_front: vec![f64::INFINITY; no_cars],
+            idxs_front: vec![None; no_cars],
+            overtaking_attempts: vec![None; no_cars],
+            overtaking_targets: vec![None; no_cars],
+            team_order_swaps: vec![None; no_cars],
+            blue_flags: vec![None; no_cars],
+            unlapping: vec![None; no_cars],
+            race_progs_prev: vec![0.0; no_cars],
+            t_overtake_losses: vec![0.0; no_cars],
+            pitlane_starts: vec![false; no_cars],
+            t_start_losses: vec![0.0; no_cars],
+            release_holds: vec![false; no_cars],
+            telemetry: None,
             cars_list,
             drivers_list,
         };
 
+        // determine the start positions considering the grid penalties
+        let p_grids: Vec<u32> = race.cars_list.iter().map(|car| car.p_grid).collect();
+        let grid_penalties: Vec<Option<GridPenalty>> = race
+            .cars_list
+            .iter()
+            .map(|car| car.grid_penalty.to_owned())
+            .collect();
+        let grid_positions = determine_grid_positions(&p_grids, &grid_penalties);
+
         // initialize race for each car
-        for idx in 0..race.cars_list.len() {
+        for (idx, grid_position) in grid_positions.into_iter().enumerate() {
             // calculate theoretical lap time for first lap
             race.calc_th_laptime(idx);
 
+            // schedule forced retirement if the car should not complete the first lap
+            if race.cars_list[idx].dnf_lap == Some(1) {
+                race.t_retirements[idx] = Some(0.0)
+            }
+
             // initialize state handler of the car
             let car = &mut race.cars_list[idx];
 
-            let s_track_start =
-                race.track.d_first_gridpos + (car.p_grid - 1) as f64 * race.track.d_per_gridpos;
+            // cars that start from the pit lane are placed at the end of the pit lane (one time
+            // step before the pit exit)
+            let s_track_start = match grid_position {
+                Some(p_grid) => {
+                    race.track.d_first_gridpos + (p_grid - 1) as f64 * race.track.d_per_gridpos
+                }
+                None => race.track.pit_zone[1] - race.track.pit_speedlimit * timestep_size,
+            };
 
             car.sh.initialize_state_handler(
                 race.use_drs,
@@ -150,7 +477,27 @@ impl Race {
                 race.track.drs_measurement_points.to_owned(),
                 race.track.pit_zone,
                 race.track.overtaking_zones.to_owned(),
-            )
+            );
+
+            if grid_position.is_none() {
+                car.sh.act_pitlane_start();
+                race.pitlane_starts[idx] = true;
+            }
+
+            race.race_progs_prev[idx] = car.sh.get_race_prog();
+
+            // draw the start performance of the car (cars that start from the pit lane are not
+            // affected)
+            if let Some(p_grid) = grid_position {
+                race.t_start_losses[idx] = race.calc_t_start_loss(idx, p_grid);
+            }
+
+            // draw random events and issue penalties for the first lap (the subsequent laps are
+            // handled at the lap transitions)
+            if race.t_retirements[idx].is_none() {
+                race.draw_events(idx);
+                race.handle_penalties(idx);
+            }
         }
 
         race
@@ -162,22 +509,47 @@ impl Race {
 
     /// The method simulates one time step. Execution order:
     /// 1. Increment the discretization variable (cur_racetime).
-    /// 2. Calculate the current lap time for each car based on the state after the previous time
+    /// 2. Retire those cars whose scheduled retirement time is reached (e.g. after an accident).
+    /// 3. Handle the full course yellow phases, i.e. start and end of the phases and deployment of
+    /// the safety car.
+    /// 4. Check the release of those cars that would leave their pit box in the current time step,
+    /// i.e. hold them in their box if a car in the fast lane is approaching.
+    /// 5. Calculate the current lap time for each car based on the state after the previous time
     /// step. The current lap time depends, for example, on the fuel mass, the age of the tires, the
     /// interactions between the drivers, and random influences. If a car is in standstill state
-    /// during a pit stop, its lap time is infinite.
-    /// 3. Update the race progress of each car for the given time step based on its current lap
-    /// time.
-    /// 4. Handle the situation if any car enters or leaves the pit standstill state in the current
+    /// during a pit stop or retired, its lap time is infinite.
+    /// 6. Update the race progress of each car (and the safety car) for the given time step based
+    /// on its current lap time.
+    /// 7. Record the race times at which the cars passed the sector boundaries and the DRS
+    /// measurement points in the current time step.
+    /// 8. Handle position changes on the track, i.e. apply a time loss to the overtaken cars.
+    /// 9. Handle the situation if any car enters or leaves the pit standstill state in the current
     /// time step (if pits are located before the finish line).
-    /// 5. Handle lap transitions for those cars that reached a new lap in the current time step.
-    /// 6. Handle the situation if any car enters or leaves the pit standstill state in the current
+    /// 10. Handle lap transitions for those cars that reached a new lap in the current time step.
+    /// 11. Handle the situation if any car enters or leaves the pit standstill state in the current
     /// time step (if pits are located after the finish line).
-    /// 7. Check if any car switches to a new state for the next time step.
+    /// 12. Consult the strategy controllers of those cars that reached the pit entry in the current
+    /// time step whether they pit in the current lap.
+    /// 13. Check if any car switches to a new state for the next time step.
+    /// 14. Show blue flags to lapped cars and let lapped cars unlap themselves behind the safety
+    /// car.
+    /// 15. Let cars through according to the team orders.
+    /// 16. Evaluate the overtaking attempts of those cars that closed on the car ahead in an
+    /// overtaking zone.
+    /// 17. Record the telemetry of the time step (if activated).
     pub fn simulate_timestep(&mut self) {
         // increment discretization variable
         self.cur_racetime += self.timestep_size;
 
+        // handle retirements
+        self.handle_retirements();
+
+        // handle full course yellow phases
+        self.handle_fcy_phases();
+
+        // handle pit releases
+        self.handle_pit_releases();
+
         // adjust current lap times such that flags, DRS etc. are considered and minimum distances
         // are kept
         self.calc_cur_laptimes();
@@ -188,6 +560,16 @@ impl Race {
                 .update_race_prog(self.cur_laptimes[i], self.timestep_size)
         }
 
+        if let Some(safety_car) = self.safety_car.as_mut() {
+            safety_car.update_race_prog(self.timestep_size)
+        }
+
+        // handle intermediate timing
+        self.handle_intermediate_timing();
+
+        // handle position changes
+        self.handle_position_changes();
+
         // handle pit stop standstill part (if pits are located in front of the finish line -
         // uncommon case)
         if !self.track.pits_aft_finishline {
@@ -203,8 +585,25 @@ impl Race {
             self.handle_pit_standstill()
         }
 
+        // handle pit decisions
+        self.handle_pit_decisions();
+
         // handle state transitions
         self.handle_state_transitions();
+
+        // handle blue flags
+        self.handle_blue_flags();
+
+        // handle team orders
+        self.handle_team_orders();
+
+        // handle overtaking attempts
+        self.handle_overtaking_attempts();
+
+        // record telemetry
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            telemetry.record(self.cur_racetime, &self.cars_list, &self.cur_laptimes)
+        }
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -213,12 +612,72 @@ impl Race {
 
     /// The method calculates the laptime a driver-car combo can theoretically drive in the current
     /// lap on a free race track, i.e. if the combo is not blocked by another car, safety car etc.
+    /// The random lap time variation is drawn once per lap using the race's random number
+    /// generator, i.e. it is reproducible for a given seed.
     fn calc_th_laptime(&mut self, idx: usize) {
-        // consider base lap time as well as driver-specific time losses and gains based on tire
-        // degradation and fuel mass loss
+        // draw random lap time variation of the driver (standard deviation is scaled by the
+        // track-specific factor)
+        let t_lap_var_sigma = self.cars_list[idx].driver.t_lap_var_sigma * self.track.f_lap_var;
+        let t_lap_var = Normal::new(0.0, t_lap_var_sigma)
+            .expect("Invalid standard deviation of the lap time variation!")
+            .sample(&mut self.rng);
+
+        // consider time losses due to the track wetness (depending on the compound) and due to
+        // overheating tires (wet tires on a drying track)
+        let car = &self.cars_list[idx];
+
+        let t_weather = match self.weather.as_ref() {
+            Some(weather) => {
+                let wetness = weather.get_wetness(self.cur_racetime);
+                weather.calc_t_wetness(car.get_compound(), wetness)
+                    + weather.calc_t_overheating(car.get_compound(), car.get_tire_overheating())
+            }
+            None => 0.0,
+        };
+
+        // consider base lap time (reduced by the current track evolution) as well as
+        // driver-specific time losses and gains based on tire degradation and fuel mass loss
         self.cur_th_laptimes[idx] = self.track.t_q
             + self.track.t_gap_racepace
-            + self.cars_list[idx].calc_basic_timeloss(self.track.s_mass)
+            + self.track.get_t_evo(self.evo_carlaps)
+            + car.calc_basic_timeloss(self.track.s_mass)
+            + t_weather
+            + t_lap_var
+    }
+
+    /// The method draws the additional time loss of the car at the inserted index until turn 1 if
+    /// the stochastic start model is activated. It consists of the reaction time and the launch of
+    /// the driver as well as of the time loss on the dirty side of the grid.
+    fn calc_t_start_loss(&mut self, idx: usize, p_grid: u32) -> f64 {
+        let start_pars = match self.start_pars.as_ref() {
+            Some(start_pars) => start_pars,
+            None => return 0.0,
+        };
+
+        let mut t_start_loss = if (p_grid % 2 == 1) == start_pars.pole_side_dirty {
+            start_pars.t_loss_dirty_side
+        } else {
+            0.0
+        };
+
+        if let Some(start_perf_pars) = self.cars_list[idx].driver.start_perf_pars.as_ref() {
+            let t_reaction = Normal::new(
+                start_perf_pars.t_reaction_mean,
+                start_perf_pars.t_reaction_sigma,
+            )
+            .expect("Invalid standard deviation of the reaction time!")
+            .sample(&mut self.rng);
+            let t_launch = Normal::new(
+                start_perf_pars.t_launch_mean,
+                start_perf_pars.t_launch_sigma,
+            )
+            .expect("Invalid standard deviation of the launch time loss!")
+            .sample(&mut self.rng);
+
+            t_start_loss += t_reaction.max(0.0) + t_launch;
+        }
+
+        t_start_loss
     }
 
     /// The method adjusts the theoretical lap times such that environmental effects are considered.
@@ -229,13 +688,20 @@ impl Race {
     /// or it is allowed to try to overtake.
     fn calc_cur_laptimes(&mut self) {
         for (i, car) in self.cars_list.iter().enumerate() {
+            // retired cars do not move anymore
+            if self.retirement_laps[i].is_some() {
+                self.cur_laptimes[i] = f64::INFINITY;
+                continue;
+            }
+
             // reset lap time
             self.cur_laptimes[i] = self.cur_th_laptimes[i];
 
             // consider race start from a standstill (time loss due to grid position is already
             // included by a negative value of the s coordinate at the race start)
             if car.sh.start_act {
-                self.cur_laptimes[i] += self.track.t_loss_firstlap / self.track.turn_1_lap_frac
+                self.cur_laptimes[i] += (self.track.t_loss_firstlap + self.t_start_losses[i])
+                    / self.track.turn_1_lap_frac
             }
 
             // consider lap time loss caused by duelling (fully applied in overtaking zones)
@@ -250,6 +716,20 @@ impl Race {
                     self.track.t_drseffect / self.track.overtaking_zones_lap_frac;
             }
 
+            // consider lap time loss due to dirty air when following another car closely (applied
+            // outside overtaking zones)
+            self.cur_laptimes[i] += self.t_dirty_air * self.calc_dirty_air_intensity(i)
+                / (1.0 - self.track.overtaking_zones_lap_frac);
+
+            // consider time loss of an overtaken car or of a car that was involved in an incident
+            // (the time loss is spread over several time steps such that at most half of a time
+            // step is lost per time step)
+            if self.t_overtake_losses[i] > 0.0 && !car.sh.pit_act {
+                let t_loss = self.t_overtake_losses[i].min(0.5 * self.timestep_size);
+                self.cur_laptimes[i] *= self.timestep_size / (self.timestep_size - t_loss);
+                self.t_overtake_losses[i] -= t_loss;
+            }
+
             // consider time loss due to a pit stop
             if car.sh.pit_act {
                 if !car.sh.pit_standstill_act {
@@ -279,19 +759,101 @@ impl Race {
                 }
             }
 
-            // consider current flag state (minimum lap time) if car is not in the pit lane
-            if !car.sh.pit_act && self.cur_laptimes[i] < self.get_min_laptime_flag_state() {
-                self.cur_laptimes[i] = self.get_min_laptime_flag_state()
+            // consider current flag state (minimum lap time) if car is not in the pit lane (cars
+            // that unlap themselves behind the safety car drive at yellow flag pace)
+            let min_laptime = if self.unlapping[i].is_some() {
+                self.get_min_laptime_flag_state(&FlagState::Y)
+            } else {
+                self.get_min_laptime_flag_state(&self.flag_state)
+            };
+
+            if !car.sh.pit_act && self.cur_laptimes[i] < min_laptime {
+                self.cur_laptimes[i] = min_laptime
+            }
+
+            // consider the VSC delta if car is not in the pit lane, i.e. the car must not get ahead
+            // of its reference, which drives at the VSC lap time from the position of the car at
+            // the start of the phase (a car that fell behind its reference, e.g. due to a pit stop,
+            // may catch up with it)
+            if matches!(self.flag_state, FlagState::Vsc | FlagState::VscEnding) && !car.sh.pit_act {
+                let laptime_vsc = (self.track.t_q + self.track.t_gap_racepace) * self.f_laptime_vsc;
+                let race_prog_ref = self.race_progs_vsc_start[i]
+                    + (self.cur_racetime - self.t_vsc_start) / laptime_vsc;
+                let lap_frac_max = race_prog_ref - car.sh.get_race_prog();
+
+                if lap_frac_max <= 0.0 {
+                    self.cur_laptimes[i] = f64::INFINITY
+                } else if self.cur_laptimes[i] < self.timestep_size / lap_frac_max {
+                    self.cur_laptimes[i] = self.timestep_size / lap_frac_max
+                }
+            }
+        }
+
+        // increase tire wear of the cars that follow another car closely (the additional tire age
+        // is considered when the theoretical lap time of the next lap is calculated)
+        for i in 0..self.cars_list.len() {
+            let f_dirty_air = self.calc_dirty_air_intensity(i);
+
+            if f_dirty_air > 0.0 {
+                let lap_frac = self.timestep_size / self.cur_th_laptimes[i];
+                self.cars_list[i]
+                    .add_tire_age_dirty_air(self.f_degr_dirty_air * f_dirty_air * lap_frac)
             }
         }
 
         // ADJUST LAP TIME IF TOO CLOSE TO CAR IN FRONT AND NOT IN OVERTAKING STATE ----------------
         // Using the car with the biggest gap in front as a starting point has the advantage that
         // its velocity/lap time does not need to be adjusted (at least that is assumed) and
-        // therefore no extra loop is required.
+        // therefore no extra loop is required. If the safety car is on the track, the car directly
+        // behind it is used as the starting point instead and its lap time is adjusted to the
+        // safety car. Cars in the pit lane do not interact with the cars on the track.
+        let mut idxs_sorted = self.get_idx_list_sorted_by_biggest_gap();
+
+        if idxs_sorted.is_empty() {
+            return;
+        }
+
+        let min_t_dist = if matches!(self.flag_state, FlagState::Sc) {
+            self.min_t_dist_sc
+        } else {
+            self.min_t_dist
+        };
+
+        if let Some(safety_car) = self.safety_car.as_ref() {
+            // rotate idxs_sorted to start with the index of the car directly behind the safety car
+            // (cars that unlap themselves are allowed to pass the safety car)
+            let delta_lap_fracs: Vec<f64> = idxs_sorted
+                .iter()
+                .map(|&idx| {
+                    if self.unlapping[idx].is_some() {
+                        f64::INFINITY
+                    } else {
+                        calc_delta_lap_frac(
+                            safety_car.get_lap_frac(),
+                            self.cars_list[idx].sh.get_lap_fracs().1,
+                        )
+                    }
+                })
+                .collect();
+
+            idxs_sorted.rotate_left(argmin(&delta_lap_fracs));
+
+            // adjust lap time of the car directly behind the safety car (same procedure as below)
+            let idx_rear = idxs_sorted[0];
+            let delta_t_proj = self.calc_projected_delta_t_sc(idx_rear, self.timestep_size);
+
+            if delta_t_proj < self.min_t_dist_sc {
+                let delta_t_cur = self.calc_projected_delta_t_sc(idx_rear, 0.0);
+                let t_gap_add =
+                    (self.min_t_dist_sc - delta_t_cur) / 3.0 * self.cur_laptimes[idx_rear];
+
+                if self.cur_laptimes[idx_rear] < safety_car.laptime + t_gap_add {
+                    self.cur_laptimes[idx_rear] = safety_car.laptime + t_gap_add
+                }
+            }
+        }
 
         // iterate through the cars pair-wise and check their temporal distance
-        let idxs_sorted = self.get_idx_list_sorted_by_biggest_gap();
         let car_pair_idxs_list = self.get_car_pair_idxs_list(&idxs_sorted, true);
 
         for pair_idxs in car_pair_idxs_list.iter() {
@@ -304,17 +866,14 @@ impl Race {
             // of the rear car (at least to the lap time of the car in front if required minimum
             // distance is currently kept) -> this also catches the case that the rear car overtakes
             // the car in front by accident due to a suddenly reduced lap time
-            if !self.cars_list[pair_idxs[1]].sh.overtaking_act
-                && !self.cars_list[pair_idxs[0]].sh.pit_act
-                && delta_t_proj < self.min_t_dist
+            if !self.get_overtaking_allowed(pair_idxs[0], pair_idxs[1]) && delta_t_proj < min_t_dist
             {
                 // calculate current temporal distance to determine new lap time
                 let delta_t_cur = self.calc_projected_delta_t(pair_idxs[0], pair_idxs[1], 0.0);
 
                 // calculate time that must be added to increase temporal distance to car in front
                 // to the desired value min_t_dist within 3 seconds
-                let t_gap_add =
-                    (self.min_t_dist - delta_t_cur) / 3.0 * self.cur_laptimes[pair_idxs[1]];
+                let t_gap_add = (min_t_dist - delta_t_cur) / 3.0 * self.cur_laptimes[pair_idxs[1]];
 
                 // apply lap time (if it is not already slow enough)
                 if self.cur_laptimes[pair_idxs[1]] < self.cur_laptimes[pair_idxs[0]] + t_gap_add {
@@ -322,25 +881,128 @@ impl Race {
                 }
             }
         }
+
+        // KEEP ORDER OF THE CARS IN THE FAST LANE -------------------------------------------------
+        // If the pit lane interaction is considered, the cars driving in the fast lane keep their
+        // order and the minimum distance, i.e. a car that was released from its box directly in
+        // front of another car blocks it.
+        if self.pit_lane_pars.is_some() {
+            // sort the cars by their distance to the pit entry (the pit lane can span the finish
+            // line)
+            let mut idxs_fast_lane: Vec<usize> = (0..self.cars_list.len())
+                .filter(|&idx| {
+                    self.retirement_laps[idx].is_none()
+                        && self.cars_list[idx].sh.pit_act
+                        && !self.cars_list[idx].sh.pit_standstill_act
+                })
+                .collect();
+
+            let s_pit_lane = |idx: usize| {
+                (self.cars_list[idx].sh.get_s_tracks().1 - self.track.pit_zone[0])
+                    .rem_euclid(self.track.length)
+            };
+            idxs_fast_lane.sort_by(|&a, &b| s_pit_lane(b).partial_cmp(&s_pit_lane(a)).unwrap());
+
+            for pair_idxs in idxs_fast_lane.windows(2) {
+                let delta_t_proj =
+                    self.calc_projected_delta_t(pair_idxs[0], pair_idxs[1], self.timestep_size);
+
+                if delta_t_proj < self.min_t_dist {
+                    let delta_t_cur = self.calc_projected_delta_t(pair_idxs[0], pair_idxs[1], 0.0);
+                    let t_gap_add =
+                        (self.min_t_dist - delta_t_cur) / 3.0 * self.cur_laptimes[pair_idxs[1]];
+
+                    if self.cur_laptimes[pair_idxs[1]] < self.cur_laptimes[pair_idxs[0]] + t_gap_add
+                    {
+                        self.cur_laptimes[pair_idxs[1]] =
+                            self.cur_laptimes[pair_idxs[0]] + t_gap_add
+                    }
+                }
+            }
+        }
     }
 
-    /// The method returns a minimum lap time that must be kept in dependence of the current flag
-    /// state.
-    fn get_min_laptime_flag_state(&self) -> f64 {
-        match self.flag_state {
+    /// The method checks if the rear car is allowed to pass the front car, i.e. if the race start
+    /// is active (free overtaking until turn 1), if the rear car overtakes the front car after a
+    /// successful overtaking attempt (or is in an overtaking zone if the overtaking attempts are
+    /// not modeled), if the front car is lapped and lets the rear car through, or if the rear car
+    /// unlaps itself behind the safety car.
+    fn get_overtaking_allowed(&self, idx_front: usize, idx_rear: usize) -> bool {
+        let yielded = matches!(
+            self.blue_flags[idx_front],
+            Some(BlueFlag {
+                idx_lapping,
+                yielded: true,
+                ..
+            }) if idx_lapping == idx_rear
+        );
+        let unlapping = matches!(self.unlapping[idx_rear], Some(Unlapping::Passing(_)))
+            && self.cars_list[idx_front].sh.get_race_prog()
+                > self.cars_list[idx_rear].sh.get_race_prog();
+
+        self.cars_list[idx_rear].sh.start_act
+            || self.overtaking_targets[idx_rear] == Some(idx_front)
+            || self.track.overtaking_difficulty.is_none()
+                && self.cars_list[idx_rear].sh.overtaking_act
+            || yielded
+            || unlapping
+    }
+
+    /// The method returns the intensity of the dirty air the car at the inserted index is exposed
+    /// to. It decreases linearly from 1.0 (directly behind the car in front) to 0.0 (gap equal to
+    /// dirty_air_window). Cars in overtaking zones, in the pit lane, at the race start, and during
+    /// SC and VSC phases are not affected.
+    fn calc_dirty_air_intensity(&self, idx: usize) -> f64 {
+        let car = &self.cars_list[idx];
+
+        if car.sh.overtaking_act
+            || car.sh.pit_act
+            || car.sh.start_act
+            || matches!(
+                self.flag_state,
+                FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+            )
+            || self.delta_ts_front[idx] >= self.dirty_air_window
+        {
+            return 0.0;
+        }
+
+        1.0 - self.delta_ts_front[idx] / self.dirty_air_window
+    }
+
+    /// The method returns a minimum lap time that must be kept in dependence of the inserted flag
+    /// state. During a safety car phase, the minimum lap time is derived from the lap time of the
+    /// safety car such that the cars can still catch up with the safety car (or the end of the
+    /// queue behind it). During a virtual safety car phase, the cars follow their VSC delta
+    /// reference instead (see calc_cur_laptimes).
+    fn get_min_laptime_flag_state(&self, flag_state: &FlagState) -> f64 {
+        match flag_state {
             FlagState::Y => (self.track.t_q + self.track.t_gap_racepace) * 1.1,
-            FlagState::Vsc => (self.track.t_q + self.track.t_gap_racepace) * 1.4,
-            FlagState::Sc => (self.track.t_q + self.track.t_gap_racepace) * 1.4,
+            FlagState::Sc => {
+                (self.track.t_q + self.track.t_gap_racepace)
+                    * (self.f_laptime_sc - F_LAPTIME_SC_CATCH_UP)
+            }
             _ => 0.0,
         }
     }
 
     /// The method returns an index list that is sorted in a way such that the car with the biggest
     /// spatial gap in front of it is located at the beginning of the list. The remaining cars
-    /// follow in the order in which they are driving on the track.
+    /// follow in the order in which they are driving on the track. Cars in the pit lane are not
+    /// part of the list.
     fn get_idx_list_sorted_by_biggest_gap(&self) -> Vec<usize> {
+        // get cars on the track (i.e. not in the pit lane)
+        let mut idx_list_sorted: Vec<usize> = self
+            .get_car_order_on_track()
+            .into_iter()
+            .filter(|&idx| !self.cars_list[idx].sh.pit_act)
+            .collect();
+
+        if idx_list_sorted.len() < 2 {
+            return idx_list_sorted;
+        }
+
         // calculate gaps between the car pairs
-        let mut idx_list_sorted = self.get_car_order_on_track();
         let car_pair_idxs_list = self.get_car_pair_idxs_list(&idx_list_sorted, false);
 
         let delta_lap_fracs: Vec<f64> = car_pair_idxs_list
@@ -353,20 +1015,371 @@ impl Race {
 
         // rotate idx_list_sorted to start with the index of the car with the biggest gap in front
         // of it
-        let start_idx = (pair_idx_biggest_gap + 1) % self.cars_list.len();
+        let start_idx = (pair_idx_biggest_gap + 1) % idx_list_sorted.len();
         idx_list_sorted.rotate_left(start_idx);
 
         idx_list_sorted
     }
 
+    /// The method records the race times at which the cars passed the sector boundaries and the DRS
+    /// measurement points within the current time step. The race times are interpolated on the
+    /// basis of the part of the time step that was driven before passing the respective point
+    /// (similar to the lap transitions).
+    fn handle_intermediate_timing(&mut self) {
+        for (i, car) in self.cars_list.iter().enumerate() {
+            if self.retirement_laps[i].is_some() {
+                continue;
+            }
+
+            let (s_track_prev, s_track_cur) = car.sh.get_s_tracks();
+            let racetime_prev = self.cur_racetime - self.timestep_size;
+            let track_length = self.track.length;
+            let cur_laptime = self.cur_laptimes[i];
+            let calc_t_passed = |s_track: f64| {
+                racetime_prev
+                    + (s_track - s_track_prev).rem_euclid(track_length) / track_length * cur_laptime
+            };
+
+            // sector boundaries
+            for (j, &s_track) in [self.track.s12, self.track.s23].iter().enumerate() {
+                if car.sh.get_s_track_passed_this_step(s_track) {
+                    self.t_sector_bounds[i][j] = calc_t_passed(s_track);
+                }
+            }
+
+            // DRS measurement points (a point that was passed before crossing the finish line
+            // within the current time step belongs to the previous lap)
+            for (j, &s_track) in self.track.drs_measurement_points.iter().enumerate() {
+                if !car.sh.get_s_track_passed_this_step(s_track) {
+                    continue;
+                }
+
+                let lap = if car.sh.get_new_lap() && s_track > s_track_cur {
+                    car.sh.get_compl_lap()
+                } else {
+                    car.sh.get_compl_lap() + 1
+                };
+
+                if 0 < lap && lap <= self.tot_no_laps {
+                    self.drs_point_times[i][lap as usize][j] = calc_t_passed(s_track);
+                }
+            }
+        }
+    }
+
+    /// The method retires those cars whose scheduled retirement time is reached. Retired cars stop
+    /// where they are and are not considered in the interactions with other cars anymore.
+    fn handle_retirements(&mut self) {
+        for i in 0..self.cars_list.len() {
+            if let Some(t_retirement) = self.t_retirements[i] {
+                if t_retirement <= self.cur_racetime && !self.race_finished[i] {
+                    let lap = self.cars_list[i].sh.get_compl_lap() + 1;
+
+                    self.retirement_laps[i] = Some(lap);
+                    self.t_retirements[i] = None;
+                    self.race_events.push(RaceEvent {
+                        t_race: self.cur_racetime,
+                        lap,
+                        car_no: Some(self.cars_list[i].car_no),
+                        kind: RaceEventKind::Retirement,
+                    });
+                }
+            }
+        }
+    }
+
+    /// The method handles the full course yellow phases. A phase is activated as soon as its start
+    /// time is reached (if no other phase is active). For a safety car phase, the safety car is
+    /// deployed, its end is announced as soon as the end time is reached, and the safety car is
+    /// removed from the track when it reaches the pit entry afterward. The restart, i.e. the switch
+    /// back to the green flag, is performed in the handle_lap_transitions method when the leader
+    /// crosses the finish line. For a virtual safety car phase, the race progress of every car at
+    /// the start of the phase is stored as the starting point of its VSC delta reference (see
+    /// calc_cur_laptimes) such that the gaps between the cars are frozen. The VSC ending phase
+    /// starts at the end time, the green flag is shown t_vsc_ending afterward.
+    fn handle_fcy_phases(&mut self) {
+        // FCY phases are not relevant anymore once the chequered flag is shown
+        if matches!(self.flag_state, FlagState::C) {
+            self.cur_fcy_phase = None;
+            self.safety_car = None;
+            return;
+        }
+
+        // check if the next phase starts
+        if self.cur_fcy_phase.is_none()
+            && !self.fcy_phases.is_empty()
+            && self.fcy_phases[0].t_start <= self.cur_racetime
+        {
+            let fcy_phase = self.fcy_phases.remove(0);
+
+            match fcy_phase.phase_type {
+                FcyPhaseType::Sc => {
+                    self.deploy_safety_car();
+                    self.flag_state = FlagState::Sc;
+                }
+                FcyPhaseType::Vsc => {
+                    // the race progress at the end of the previous time step is the reference
+                    self.flag_state = FlagState::Vsc;
+                    self.t_vsc_start = self.cur_racetime - self.timestep_size;
+                    self.race_progs_vsc_start = self
+                        .cars_list
+                        .iter()
+                        .map(|car| car.sh.get_race_prog())
+                        .collect();
+                }
+            }
+
+            self.race_events.push(RaceEvent {
+                t_race: self.cur_racetime,
+                lap: self.cur_lap_leader,
+                car_no: None,
+                kind: RaceEventKind::FcyStart(fcy_phase.phase_type.to_owned()),
+            });
+            self.cur_fcy_phase = Some(fcy_phase);
+        }
+
+        // handle the end of the current phase
+        if let Some(fcy_phase) = self.cur_fcy_phase.to_owned() {
+            match fcy_phase.phase_type {
+                FcyPhaseType::Sc => {
+                    // announce the end of the phase (safety car enters the pits at the end of the
+                    // lap)
+                    if fcy_phase.t_end <= self.cur_racetime {
+                        if let Some(safety_car) = self.safety_car.as_mut() {
+                            safety_car.in_lap = true
+                        }
+                    }
+                }
+                FcyPhaseType::Vsc => {
+                    // end VSC ending phase (DRS is allowed again two laps afterward) or start it
+                    if fcy_phase.t_end + self.t_vsc_ending <= self.cur_racetime {
+                        self.flag_state = FlagState::G;
+                        self.cur_fcy_phase = None;
+                        self.drs_allowed_lap = self.cur_lap_leader + 2;
+                        self.race_events.push(RaceEvent {
+                            t_race: self.cur_racetime,
+                            lap: self.cur_lap_leader,
+                            car_no: None,
+                            kind: RaceEventKind::FcyEnd(FcyPhaseType::Vsc),
+                        });
+                    } else if fcy_phase.t_end <= self.cur_racetime {
+                        self.flag_state = FlagState::VscEnding
+                    }
+                }
+            }
+        }
+
+        // remove the safety car from the track if it passed the pit entry in its in-lap
+        if let Some(safety_car) = self.safety_car.as_ref() {
+            if safety_car.in_lap && safety_car.get_s_track_passed_this_step(self.track.pit_zone[0])
+            {
+                self.safety_car = None
+            }
+        }
+    }
+
+    /// The method inserts an additional FCY phase into the list of upcoming phases, e.g. if it is
+    /// triggered by an event during the race. If another phase is active at the start time of the
+    /// inserted phase, the latter is activated after the end of the active phase.
+    pub fn insert_fcy_phase(&mut self, fcy_phase: FcyPhase) {
+        let idx = self
+            .fcy_phases
+            .iter()
+            .position(|x| x.t_start > fcy_phase.t_start)
+            .unwrap_or(self.fcy_phases.len());
+        self.fcy_phases.insert(idx, fcy_phase);
+    }
+
+    /// The method activates the recording of the telemetry, i.e. the state of every car is recorded
+    /// in every decimation-th time step. It must be called before the first time step is simulated.
+    pub fn activate_telemetry(&mut self, decimation: u32) {
+        self.telemetry = Some(Telemetry::new(
+            &self.cars_list,
+            self.timestep_size,
+            decimation,
+        ))
+    }
+
+    /// The method places the safety car on the track directly in front of the race leader such
+    /// that the leader is picked up at the minimum distance.
+    fn deploy_safety_car(&mut self) {
+        // determine race leader (retired cars are not considered)
+        let race_progs: Vec<f64> = self
+            .cars_list
+            .iter()
+            .enumerate()
+            .map(|(i, car)| {
+                if self.retirement_laps[i].is_none() {
+                    car.sh.get_race_prog()
+                } else {
+                    f64::NEG_INFINITY
+                }
+            })
+            .collect();
+        let idx_leader = argmax(&race_progs);
+
+        // place safety car in front of the leader
+        let laptime_sc = (self.track.t_q + self.track.t_gap_racepace) * self.f_laptime_sc;
+        let mut s_track_sc = self.cars_list[idx_leader].sh.get_s_tracks().1
+            + self.min_t_dist_sc / laptime_sc * self.track.length;
+
+        if s_track_sc >= self.track.length {
+            s_track_sc -= self.track.length
+        }
+
+        self.safety_car = Some(SafetyCar::new(laptime_sc, self.track.length, s_track_sc))
+    }
+
+    /// The method checks the release of the cars that would leave their pit box within the current
+    /// time step (if the pit lane interaction is considered). A car is held in its box as long as a
+    /// car in the fast lane is less than t_release_gap away from it. With the probability
+    /// p_unsafe_release, the team releases the car anyway, which is penalized.
+    fn handle_pit_releases(&mut self) {
+        let pit_lane_pars = match self.pit_lane_pars.as_ref() {
+            Some(pit_lane_pars) => pit_lane_pars.to_owned(),
+            None => return,
+        };
+
+        // determine the distance (in track coordinates) that is covered in the fast lane within
+        // t_release_gap
+        let s_release_gap = pit_lane_pars.t_release_gap
+            * self.track.pit_speedlimit
+            * self.track.track_length_pit_zone
+            / self.track.real_length_pit_zone;
+
+        // get positions of the cars in the fast lane
+        let s_tracks_fast_lane: Vec<f64> = self
+            .cars_list
+            .iter()
+            .enumerate()
+            .filter(|&(i, car)| {
+                self.retirement_laps[i].is_none() && car.sh.pit_act && !car.sh.pit_standstill_act
+            })
+            .map(|(_, car)| car.sh.get_s_tracks().1)
+            .collect();
+
+        for i in 0..self.cars_list.len() {
+            let car = &self.cars_list[i];
+
+            if self.retirement_laps[i].is_some()
+                || self.pitlane_starts[i]
+                || !car.sh.pit_standstill_act
+                || car.sh.check_leaves_standstill(self.timestep_size).is_none()
+            {
+                continue;
+            }
+
+            // check if a car in the fast lane is approaching the pit box
+            let s_track_box = car.sh.get_s_tracks().1;
+            let conflict = s_tracks_fast_lane.iter().any(|&s_track| {
+                let delta_s = (s_track_box - s_track).rem_euclid(self.track.length);
+                delta_s > 0.0 && delta_s < s_release_gap
+            });
+
+            if !conflict {
+                self.release_holds[i] = false;
+                continue;
+            }
+
+            // the team decides once per release whether it holds the car or releases it unsafely
+            // (the pit stop record is not available if the car only serves a penalty)
+            let car_no = car.car_no;
+            let inlap = if self.track.pits_aft_finishline {
+                car.sh.get_compl_lap()
+            } else {
+                car.sh.get_compl_lap() + 1
+            };
+            let pit_stop = self
+                .pit_stops
+                .iter_mut()
+                .find(|pit_stop| pit_stop.car_no == car_no && pit_stop.inlap == inlap);
+
+            if !self.release_holds[i] && self.rng.gen_bool(pit_lane_pars.p_unsafe_release) {
+                let lap = car.sh.get_compl_lap() + 1;
+
+                if let Some(pit_stop) = pit_stop {
+                    pit_stop.unsafe_release = true
+                }
+
+                self.cars_list[i].add_penalty(pit_lane_pars.penalty_unsafe_release, lap);
+
+                for kind in [
+                    RaceEventKind::UnsafeRelease,
+                    RaceEventKind::Penalty(pit_lane_pars.penalty_unsafe_release),
+                ] {
+                    self.race_events.push(RaceEvent {
+                        t_race: self.cur_racetime,
+                        lap,
+                        car_no: Some(car_no),
+                        kind,
+                    })
+                }
+            } else {
+                if let Some(pit_stop) = pit_stop {
+                    pit_stop.t_release_hold += self.timestep_size;
+                    pit_stop.t_standstill += self.timestep_size;
+                }
+
+                self.cars_list[i].sh.hold_pit_standstill(self.timestep_size);
+                self.release_holds[i] = true;
+            }
+        }
+    }
+
     /// The method checks if any car reaches the pit location within the current time step and
     /// activates the pit standstill state in that case. If a car is already in standstill state,
     /// the method assures that the standstill time is increased and that it leaves the state as
     /// soon as it exceedes the target time. The pit stop itself (i.e. refueling and tire change) is
     /// nevertheless executed in the handle_lap_transition method to avoid issues due to wrong tire
-    /// age etc.
+    /// age etc. Cars that start from the pit lane are released as soon as all cars that started from
+    /// the grid passed the pit exit. If the pit lane interaction is considered, a car that reaches
+    /// its pit box while its teammate is still in standstill waits until the box is free (double
+    /// stacking). The waiting car is placed at the pit location directly, i.e. the queue is modelled
+    /// as additional standstill time (t_queue) and does not block the fast lane.
     fn handle_pit_standstill(&mut self) {
+        if self.pitlane_starts.contains(&true) {
+            let lap_frac_pit_exit = self.track.pit_zone[1] / self.track.length;
+
+            let field_passed = self.cars_list.iter().enumerate().all(|(i, car)| {
+                self.pitlane_starts[i]
+                    || self.retirement_laps[i].is_some()
+                    || car.sh.get_race_prog() >= lap_frac_pit_exit
+            });
+
+            if field_passed {
+                for i in 0..self.cars_list.len() {
+                    if self.pitlane_starts[i] {
+                        self.cars_list[i].sh.release_pit_standstill();
+                        self.pitlane_starts[i] = false;
+                    }
+                }
+            }
+        }
+
+        // determine the remaining standstill times of the cars in the pit boxes of the teams (only
+        // required if the pit lane interaction is considered)
+        let mut t_boxes_occupied: HashMap<String, f64> = HashMap::new();
+
+        if self.pit_lane_pars.is_some() {
+            for (i, car) in self.cars_list.iter().enumerate() {
+                if self.retirement_laps[i].is_some()
+                    || self.pitlane_starts[i]
+                    || !car.sh.pit_standstill_act
+                {
+                    continue;
+                }
+
+                let t_box_occupied = t_boxes_occupied.entry(car.team.to_owned()).or_insert(0.0);
+                *t_box_occupied = t_box_occupied.max(car.sh.get_t_standstill_remaining());
+            }
+        }
+
         for (i, car) in self.cars_list.iter_mut().enumerate() {
+            // retired cars are not considered anymore
+            if self.retirement_laps[i].is_some() {
+                continue;
+            }
+
             // check for possible activation of standstill state if car is within the pit and not
             // already in standstill state
             if car.sh.pit_act && !car.sh.pit_standstill_act {
@@ -396,17 +1409,69 @@ impl Race {
                 // below this line we handle the case that the car enters the standstill state
                 // within the current step ---------------------------------------------------------
 
-                // determine standstill target time for current pit stop
+                // determine standstill target time for current pit stop and serve the pending
+                // penalties (a drive-through does not require a standstill)
                 let compl_lap_cur = car.sh.get_compl_lap();
-                let t_standstill_target = if self.track.pits_aft_finishline {
-                    car.t_add_pit_standstill(compl_lap_cur)
+                let inlap = if self.track.pits_aft_finishline {
+                    compl_lap_cur
                 } else {
-                    car.t_add_pit_standstill(compl_lap_cur + 1)
+                    compl_lap_cur + 1
+                };
+                let (t_pit_tirechange, slow_stop) = match self.pit_stop_pars.get(&car.team) {
+                    Some(pit_stop_pars) if car.changes_tires(inlap) => {
+                        pit_stop_pars.draw_t_tirechange(car.get_t_pit_tirechange(), &mut self.rng)
+                    }
+                    _ => (car.get_t_pit_tirechange(), false),
                 };
+                let mut t_standstill_target =
+                    car.t_add_pit_standstill(inlap, t_pit_tirechange, &self.penalty_pars);
+                let drive_through = car.get_drive_through(inlap);
+
+                car.serve_penalties(inlap);
+
+                if drive_through {
+                    continue;
+                }
+
+                // the car must wait until its teammate left the pit box (double stacking)
+                let t_queue = t_boxes_occupied.get(&car.team).copied().unwrap_or(0.0);
+                t_standstill_target += t_queue;
+
+                if self.pit_lane_pars.is_some() {
+                    t_boxes_occupied.insert(
+                        car.team.to_owned(),
+                        t_standstill_target - (self.timestep_size - t_part_drive),
+                    );
+                }
 
                 // set car state to pit standstill and set standstill time that was already achieved
                 car.sh
                     .act_pit_standstill(self.timestep_size - t_part_drive, t_standstill_target);
+                self.race_events.push(RaceEvent {
+                    t_race: self.cur_racetime - self.timestep_size + t_part_drive,
+                    lap: compl_lap_cur + 1,
+                    car_no: Some(car.car_no),
+                    kind: RaceEventKind::StandstillStart,
+                });
+
+                // record the pit stop (pit lane visits to serve a penalty are not recorded)
+                if car.pit_this_lap(inlap) {
+                    self.pit_stops.push(PitStop {
+                        car_no: car.car_no,
+                        inlap,
+                        t_race: self.cur_racetime - self.timestep_size + t_part_drive,
+                        t_tirechange: if car.changes_tires(inlap) {
+                            t_pit_tirechange
+                        } else {
+                            0.0
+                        },
+                        t_standstill: t_standstill_target,
+                        slow_stop,
+                        t_queue,
+                        t_release_hold: 0.0,
+                        unsafe_release: false,
+                    })
+                }
 
                 // update race progress of the car such that it is placed exactly at the pit
                 // location
@@ -414,15 +1479,20 @@ impl Race {
             } else if car.sh.pit_standstill_act {
                 // if standstill is active currently, it must be checked if the car stays or leaves
                 // it within the current time step
-                let leaves_standstill =
-                    car.sh.check_leaves_standstill(self.timestep_size).is_some();
+                let t_part_drive = car.sh.check_leaves_standstill(self.timestep_size);
 
-                if !leaves_standstill {
+                if let Some(t_part_drive) = t_part_drive {
+                    // car leaves standstill state within current time step
+                    car.sh.deact_pit_standstill();
+                    self.race_events.push(RaceEvent {
+                        t_race: self.cur_racetime - t_part_drive,
+                        lap: car.sh.get_compl_lap() + 1,
+                        car_no: Some(car.car_no),
+                        kind: RaceEventKind::StandstillEnd,
+                    });
+                } else {
                     // car remains in standstill, therefore increment standstill time
                     car.sh.increment_t_standstill(self.timestep_size)
-                } else {
-                    // car leaves standstill state within current time step
-                    car.sh.deact_pit_standstill()
                 }
             }
         }
@@ -434,6 +1504,8 @@ impl Race {
         // check at first if race was finished by any car such that checkered flag can be considered
         // in the loop afterward (required since cars cannot complete all laps if they were lapped,
         // for example)
+        let cur_lap_leader_prev = self.cur_lap_leader;
+
         for car in self.cars_list.iter() {
             let compl_lap_cur = car.sh.get_compl_lap();
 
@@ -442,6 +1514,29 @@ impl Race {
             }
         }
 
+        // restart the race if the leader crosses the finish line after the safety car left the
+        // track (DRS is allowed again two laps after the restart)
+        if self.cur_lap_leader > cur_lap_leader_prev
+            && self.safety_car.is_none()
+            && matches!(
+                self.cur_fcy_phase,
+                Some(FcyPhase {
+                    phase_type: FcyPhaseType::Sc,
+                    ..
+                })
+            )
+        {
+            self.flag_state = FlagState::G;
+            self.cur_fcy_phase = None;
+            self.drs_allowed_lap = self.cur_lap_leader + 2;
+            self.race_events.push(RaceEvent {
+                t_race: self.cur_racetime,
+                lap: self.cur_lap_leader,
+                car_no: None,
+                kind: RaceEventKind::FcyEnd(FcyPhaseType::Sc),
+            });
+        }
+
         if self.cur_lap_leader > self.tot_no_laps && !matches!(self.flag_state, FlagState::C) {
             self.flag_state = FlagState::C
         }
@@ -456,22 +1551,73 @@ impl Race {
                 let lap_frac_prev = car.sh.get_lap_fracs().0;
                 let t_part_old = (1.0 - lap_frac_prev) * self.cur_laptimes[i];
 
-                // update lap time and race time arrays (if laps are part of the race)
+                // update lap time, race time, and compound arrays (if laps are part of the race)
                 let compl_lap_cur = car.sh.get_compl_lap();
 
                 if compl_lap_cur <= self.tot_no_laps {
+                    self.compounds[i][compl_lap_cur as usize] = car.get_compound().to_owned();
                     self.laptimes[i][compl_lap_cur as usize] =
                         self.cur_racetime - self.timestep_size + t_part_old
                             - self.racetimes[i][compl_lap_cur as usize - 1];
                     self.racetimes[i][compl_lap_cur as usize] = self.racetimes[i]
                         [compl_lap_cur as usize - 1]
                         + self.laptimes[i][compl_lap_cur as usize];
+
+                    // determine sector times (only if both sector boundaries were passed within
+                    // the lap)
+                    let racetime_prev = self.racetimes[i][compl_lap_cur as usize - 1];
+                    let [t_s12, t_s23] = self.t_sector_bounds[i];
+
+                    if racetime_prev <= t_s12 && t_s12 <= t_s23 {
+                        self.sectortimes[i][compl_lap_cur as usize] = [
+                            t_s12 - racetime_prev,
+                            t_s23 - t_s12,
+                            self.racetimes[i][compl_lap_cur as usize] - t_s23,
+                        ];
+                    }
                 }
 
                 // set race finished for current car if it crosses the line after the chequered flag
                 // got active
                 if matches!(self.flag_state, FlagState::C) {
-                    self.race_finished[i] = true
+                    // the chequered flag is shown when the first car crosses the line
+                    if !self.race_finished.contains(&true) {
+                        self.race_events.push(RaceEvent {
+                            t_race: self.racetimes[i][compl_lap_cur as usize],
+                            lap: self.tot_no_laps,
+                            car_no: None,
+                            kind: RaceEventKind::ChequeredFlag,
+                        });
+                    }
+
+                    self.race_finished[i] = true;
+                    self.race_events.push(RaceEvent {
+                        t_race: self.racetimes[i][compl_lap_cur as usize],
+                        lap: compl_lap_cur,
+                        car_no: Some(car.car_no),
+                        kind: RaceEventKind::Finish,
+                    });
+                }
+
+                // accumulate tire overheating if the lap was driven on a too dry track for the
+                // mounted compound
+                let wetness = match self.weather.as_ref() {
+                    Some(weather) => weather.get_wetness(self.cur_racetime),
+                    None => 0.0,
+                };
+
+                if let Some(weather) = self.weather.as_ref() {
+                    car.add_tire_overheating(weather.calc_overheating(car.get_compound(), wetness));
+                }
+
+                // update track evolution: the track rubbers in with every completed lap if it is
+                // dry, whereas rain washes the rubber away
+                if compl_lap_cur <= self.tot_no_laps {
+                    if wetness > 0.0 {
+                        self.evo_carlaps *= 1.0 - self.track.f_evo_reset_wet * wetness
+                    } else {
+                        self.evo_carlaps += 1.0
+                    }
                 }
 
                 // increase car age by a lap
@@ -486,12 +1632,264 @@ impl Race {
 
                 // update theoretical lap time
                 self.calc_th_laptime(i);
+
+                // schedule forced retirement or draw random events for the new lap (if it is part of
+                // the race)
+                if self.cars_list[i].dnf_lap == Some(compl_lap_cur + 1) {
+                    self.t_retirements[i] = Some(self.cur_racetime)
+                } else if compl_lap_cur < self.tot_no_laps
+                    && !matches!(self.flag_state, FlagState::C)
+                    && self.t_retirements[i].is_none()
+                {
+                    self.draw_events(i);
+                    self.handle_penalties(i);
+                }
             }
         }
     }
 
-    /// The method prepares the required data for the car statemachine state-transition check, and
-    /// calls it.
+    /// The method draws random accidents and technical failures of the car at the inserted index
+    /// for the lap it just started. The track-specific accident probability and the
+    /// season-specific failure probability are used if the driver or the car does not provide an
+    /// own probability. An event happens at a random point in time within the lap.
+    fn draw_events(&mut self, idx: usize) {
+        let p_accident = self.cars_list[idx]
+            .driver
+            .p_accident
+            .unwrap_or(self.track.p_accident);
+        let p_failure = self.cars_list[idx].p_failure.unwrap_or(self.p_failure);
+
+        if self.rng.gen_bool(p_accident) {
+            self.handle_event(idx, RaceEventKind::Accident)
+        } else if self.rng.gen_bool(p_failure) {
+            self.handle_event(idx, RaceEventKind::Failure)
+        }
+    }
+
+    /// The method issues the scheduled penalties of the car at the inserted index for the lap it
+    /// just started and draws a random penalty (if the penalty probability is greater than zero).
+    /// Issued penalties are recorded as race events.
+    fn handle_penalties(&mut self, idx: usize) {
+        let lap = self.cars_list[idx].sh.get_compl_lap() + 1;
+        let mut penalty_types = self.cars_list[idx].issue_scheduled_penalties(lap);
+
+        if self.penalty_pars.p_penalty > 0.0 && self.rng.gen_bool(self.penalty_pars.p_penalty) {
+            let dist = WeightedIndex::new(self.penalty_pars.p_penalty_types)
+                .expect("Invalid probabilities of the penalty types!");
+            let penalty_type = PENALTY_TYPES[dist.sample(&mut self.rng)];
+
+            self.cars_list[idx].add_penalty(penalty_type, lap);
+            penalty_types.push(penalty_type);
+        }
+
+        for penalty_type in penalty_types.into_iter() {
+            self.race_events.push(RaceEvent {
+                t_race: self.cur_racetime,
+                lap,
+                car_no: Some(self.cars_list[idx].car_no),
+                kind: RaceEventKind::Penalty(penalty_type),
+            });
+        }
+    }
+
+    /// The method draws the race time of an accident or a technical failure of the car at the
+    /// inserted index within its current lap and inserts the event.
+    fn handle_event(&mut self, idx: usize, kind: RaceEventKind) {
+        let t_event = self.cur_racetime + self.rng.gen::<f64>() * self.cur_th_laptimes[idx];
+        self.insert_event(idx, kind, t_event)
+    }
+
+    /// The method records an accident or a technical failure of the car at the inserted index and
+    /// race time, schedules the retirement of the car, and translates the event into an FCY phase
+    /// if required. An accident leads either to a safety car or to a virtual safety car phase, a
+    /// technical failure leads either to a virtual safety car phase or to no FCY phase at all (see
+    /// EventPars). No additional phase is created if another phase is currently active or overlaps
+    /// with the new phase.
+    fn insert_event(&mut self, idx: usize, kind: RaceEventKind, t_event: f64) {
+        self.race_events.push(RaceEvent {
+            t_race: t_event,
+            lap: self.cars_list[idx].sh.get_compl_lap() + 1,
+            car_no: Some(self.cars_list[idx].car_no),
+            kind: kind.to_owned(),
+        });
+        self.t_retirements[idx] = Some(t_event);
+
+        // determine type of the resulting FCY phase
+        let phase_type = match kind {
+            RaceEventKind::Accident => {
+                if self.rng.gen_bool(self.event_pars.p_sc_accident) {
+                    FcyPhaseType::Sc
+                } else {
+                    FcyPhaseType::Vsc
+                }
+            }
+            RaceEventKind::Failure => {
+                if self.rng.gen_bool(self.event_pars.p_vsc_failure) {
+                    FcyPhaseType::Vsc
+                } else {
+                    return;
+                }
+            }
+            _ => panic!("Unknown event kind {:?}!", kind),
+        };
+
+        // sample duration of the phase (the number of laps of a safety car phase is converted into
+        // a duration using the lap time of the safety car)
+        let t_duration = match phase_type {
+            FcyPhaseType::Sc => {
+                let no_laps = self.rng.gen_range(
+                    self.event_pars.sc_duration_laps[0]..=self.event_pars.sc_duration_laps[1],
+                );
+                no_laps as f64 * (self.track.t_q + self.track.t_gap_racepace) * self.f_laptime_sc
+            }
+            FcyPhaseType::Vsc => self
+                .rng
+                .gen_range(self.event_pars.vsc_duration[0]..=self.event_pars.vsc_duration[1]),
+        };
+
+        let fcy_phase = FcyPhase {
+            phase_type,
+            t_start: t_event,
+            t_end: t_event + t_duration,
+        };
+
+        // insert phase if it does not collide with an active or an upcoming phase
+        if self.cur_fcy_phase.is_none()
+            && !self
+                .fcy_phases
+                .iter()
+                .any(|x| x.t_start <= fcy_phase.t_end && fcy_phase.t_start <= x.t_end)
+        {
+            self.insert_fcy_phase(fcy_phase)
+        }
+    }
+
+    /// The method consults the strategy controller of every car that reached the pit entry within
+    /// the current time step whether it pits in the current lap. The decision is made before the
+    /// state transitions are checked, i.e. the car enters the pit lane within the same time step.
+    fn handle_pit_decisions(&mut self) {
+        if matches!(self.flag_state, FlagState::C) {
+            return;
+        }
+
+        for i in 0..self.cars_list.len() {
+            let car = &self.cars_list[i];
+
+            if self.retirement_laps[i].is_some()
+                || car.sh.pit_act
+                || !car.sh.get_s_track_passed_this_step(self.track.pit_zone[0])
+            {
+                continue;
+            }
+
+            // collect information about the rivals that are still in the race
+            let rivals = self
+                .cars_list
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i && self.retirement_laps[j].is_none())
+                .map(|(_, rival)| RivalInfo {
+                    car_no: rival.car_no,
+                    race_prog: rival.sh.get_race_prog(),
+                    compound: rival.get_compound().to_owned(),
+                    tire_age: rival.get_tire_age(),
+                    pit_act: rival.sh.pit_act,
+                    last_inlap: rival.get_last_inlap(),
+                })
+                .collect();
+
+            let strategy_info = StrategyInfo {
+                cur_lap: car.sh.get_compl_lap() + 1,
+                tot_no_laps: self.tot_no_laps,
+                race_prog: car.sh.get_race_prog(),
+                laptime: self.cur_th_laptimes[i],
+                t_pit_loss: self.track.get_pit_drive_timeloss() + car.get_t_pit_tirechange(),
+                compound: car.get_compound().to_owned(),
+                tire_age: car.get_tire_age(),
+                compounds_used: car.get_compounds_used(),
+                regulations: Rc::clone(&self.regulations),
+                driver: Rc::clone(&car.driver),
+                flag_state: self.flag_state.to_owned(),
+                wetness: match self.weather.as_ref() {
+                    Some(weather) => weather.get_wetness(self.cur_racetime),
+                    None => 0.0,
+                },
+                weather: self.weather.as_ref().map(Rc::clone),
+                rivals,
+            };
+
+            self.cars_list[i].decide_pit(&strategy_info);
+        }
+    }
+
+    /// The method detects position changes between the cars on the track within the current time
+    /// step and applies the time loss t_overtake_loser to the overtaken car. Position changes
+    /// caused by pit stops, team orders, and those during the race start (until turn 1) are not
+    /// considered. Only passes between cars that race each other under green flag are charged, i.e.
+    /// reorderings during full course yellow phases (e.g. in the safety car queue) are recorded
+    /// without a time loss. Lapping does not change the positions and is therefore never charged. Passes resulting from a successful overtaking attempt, from a lapped car yielding
+    /// under blue flags, and from a car unlapping itself behind the safety car are recorded without
+    /// an additional time loss since the passed car was already charged for them (see
+    /// handle_overtaking_attempts and handle_blue_flags) or did not fight for the position.
+    fn handle_position_changes(&mut self) {
+        let fcy_active = matches!(
+            self.flag_state,
+            FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+        );
+
+        let race_progs: Vec<f64> = self
+            .cars_list
+            .iter()
+            .map(|car| car.sh.get_race_prog())
+            .collect();
+
+        for i in 0..self.cars_list.len() {
+            for j in 0..self.cars_list.len() {
+                if i == j || self.retirement_laps[i].is_some() || self.retirement_laps[j].is_some()
+                {
+                    continue;
+                }
+
+                let sh_i = &self.cars_list[i].sh;
+                let sh_j = &self.cars_list[j].sh;
+
+                if sh_i.pit_act || sh_j.pit_act || sh_i.start_act || sh_j.start_act {
+                    continue;
+                }
+
+                // check if car j overtook car i (without being let through by a team order)
+                if self.race_progs_prev[i] > self.race_progs_prev[j]
+                    && race_progs[i] < race_progs[j]
+                    && self.team_order_swaps[j] != Some(i)
+                {
+                    let yielded = self.blue_flags[i]
+                        .as_ref()
+                        .is_some_and(|blue_flag| blue_flag.idx_lapping == j);
+
+                    if !fcy_active
+                        && self.overtaking_targets[j] != Some(i)
+                        && !yielded
+                        && self.unlapping[j].is_none()
+                    {
+                        self.t_overtake_losses[i] += self.t_overtake_loser;
+                    }
+
+                    self.race_events.push(RaceEvent {
+                        t_race: self.cur_racetime,
+                        lap: sh_j.get_compl_lap() + 1,
+                        car_no: Some(self.cars_list[j].car_no),
+                        kind: RaceEventKind::Overtake(self.cars_list[i].car_no),
+                    });
+                }
+            }
+        }
+
+        self.race_progs_prev = race_progs;
+    }
+
+    /// The method prepares the required data for the car statemachine state-transition check, and
+    /// calls it. Cars that complete the race start (i.e. pass turn 1) within the current time step
+    /// are checked for an incident in the first corner afterward.
     fn handle_state_transitions(&mut self) {
         // calculate gaps between the car pairs and check if rear car laps the car in front
         let idxs_sorted = self.get_car_order_on_track();
@@ -501,8 +1899,27 @@ impl Race {
         let mut lapping = vec![false; self.cars_list.len()];
 
         for (i, pair_idxs) in car_pair_idxs_list.iter().enumerate() {
+            // a single remaining car on the track has no car in front
+            if pair_idxs[0] == pair_idxs[1] {
+                delta_ts[i] = f64::INFINITY;
+                self.idxs_front[pair_idxs[1]] = None;
+                self.delta_ts_front[pair_idxs[1]] = f64::INFINITY;
+                continue;
+            }
+
             delta_ts[i] = self.calc_projected_delta_t(pair_idxs[0], pair_idxs[1], 0.0);
 
+            // store the car in front of the rear car and the gap to it (cars in the pit lane do not
+            // interact with the cars on the track)
+            self.idxs_front[pair_idxs[1]] = Some(pair_idxs[0]);
+            self.delta_ts_front[pair_idxs[1]] = if self.cars_list[pair_idxs[0]].sh.pit_act
+                || self.cars_list[pair_idxs[1]].sh.pit_act
+            {
+                f64::INFINITY
+            } else {
+                delta_ts[i]
+            };
+
             // race start is handled correctly since get_race_prog can be negative
             if self.cars_list[pair_idxs[0]].sh.get_race_prog()
                 < self.cars_list[pair_idxs[1]].sh.get_race_prog()
@@ -512,6 +1929,8 @@ impl Race {
         }
 
         // check for state transitions (always for the rear car)
+        let mut idxs_start_compl = vec![];
+
         for (i, pair_idxs) in car_pair_idxs_list.iter().enumerate() {
             // get lap fraction of the car
             let compl_lap_cur = self.cars_list[pair_idxs[1]].sh.get_compl_lap();
@@ -520,10 +1939,14 @@ impl Race {
             // car
             let j = (i + 1) % car_pair_idxs_list.len();
 
-            // check for state transition of the car
-            let pit_this_lap = self.cars_list[pair_idxs[1]].pit_this_lap(compl_lap_cur + 1);
+            // check for state transition of the car (a pending drive-through or stop-and-go
+            // penalty is served in the next lap without a planned pit stop before the last lap)
+            let car = &self.cars_list[pair_idxs[1]];
+            let pit_this_lap = car.pit_this_lap(compl_lap_cur + 1)
+                || (compl_lap_cur + 1 < self.tot_no_laps && car.get_pit_penalty_pending());
+            let start_act_prev = car.sh.start_act;
 
-            self.cars_list[pair_idxs[1]].sh.check_state_transition(
+            let race_event_kind = self.cars_list[pair_idxs[1]].sh.check_state_transition(
                 delta_ts[i],
                 delta_ts[j],
                 pit_this_lap,
@@ -532,7 +1955,294 @@ impl Race {
                 &self.flag_state,
                 self.cur_lap_leader,
                 self.drs_allowed_lap,
-            )
+            );
+
+            if let Some(kind) = race_event_kind {
+                self.race_events.push(RaceEvent {
+                    t_race: self.cur_racetime,
+                    lap: compl_lap_cur + 1,
+                    car_no: Some(self.cars_list[pair_idxs[1]].car_no),
+                    kind,
+                })
+            }
+
+            if start_act_prev && !self.cars_list[pair_idxs[1]].sh.start_act {
+                idxs_start_compl.push(pair_idxs[1])
+            }
+        }
+
+        for idx in idxs_start_compl.into_iter() {
+            self.handle_start_incident(idx)
+        }
+    }
+
+    /// The method draws whether the car at the inserted index is involved in an incident in the
+    /// first corner (if the stochastic start model is activated). An incident either ends the race
+    /// of the car (treated like an accident) or causes a time loss.
+    fn handle_start_incident(&mut self, idx: usize) {
+        let start_pars = match self.start_pars.as_ref() {
+            Some(start_pars) => start_pars.to_owned(),
+            None => return,
+        };
+
+        if self.retirement_laps[idx].is_some()
+            || self.t_retirements[idx].is_some()
+            || !self.rng.gen_bool(start_pars.p_incident_turn_1)
+        {
+            return;
+        }
+
+        if self.rng.gen_bool(start_pars.p_dnf_incident) {
+            self.insert_event(idx, RaceEventKind::Accident, self.cur_racetime)
+        } else {
+            self.t_overtake_losses[idx] += self
+                .rng
+                .gen_range(start_pars.t_loss_incident[0]..=start_pars.t_loss_incident[1]);
+
+            self.race_events.push(RaceEvent {
+                t_race: self.cur_racetime,
+                lap: 1,
+                car_no: Some(self.cars_list[idx].car_no),
+                kind: RaceEventKind::StartIncident,
+            });
+        }
+    }
+
+    /// The method handles the blue flags and the unlapping behind the safety car. A blue flag is
+    /// shown to a lapped car as soon as a lapping car is within blue_flag_window behind it. The
+    /// lapping car is stuck behind the lapped car until the latter lets it through after s_yield,
+    /// i.e. the lapped car drops behind the lapping car and additionally loses t_yield. If enabled,
+    /// all lapped cars are allowed to unlap themselves when the end of a safety car phase is
+    /// announced. They pass the cars in front of them (including the safety car) and catch up with
+    /// the end of the field at yellow flag pace until the race is restarted.
+    fn handle_blue_flags(&mut self) {
+        let blue_flag_pars = match self.blue_flag_pars.as_ref() {
+            Some(blue_flag_pars) => blue_flag_pars.to_owned(),
+            None => return,
+        };
+
+        let fcy_active = matches!(
+            self.flag_state,
+            FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+        );
+
+        let race_progs: Vec<f64> = self
+            .cars_list
+            .iter()
+            .map(|car| car.sh.get_race_prog())
+            .collect();
+
+        // remove blue flags if the lapping car passed the lapped car (or is not directly behind it
+        // anymore)
+        for i in 0..self.cars_list.len() {
+            if let Some(blue_flag) = self.blue_flags[i].as_ref() {
+                if fcy_active
+                    || self.retirement_laps[i].is_some()
+                    || self.cars_list[i].sh.pit_act
+                    || self.idxs_front[blue_flag.idx_lapping] != Some(i)
+                {
+                    self.blue_flags[i] = None
+                }
+            }
+        }
+
+        // show blue flags to lapped cars and let the lapping cars through after s_yield
+        for i in 0..self.cars_list.len() {
+            let car = &self.cars_list[i];
+
+            if fcy_active || self.retirement_laps[i].is_some() || car.sh.pit_act || car.sh.start_act
+            {
+                continue;
+            }
+
+            let idx_front = match self.idxs_front[i] {
+                Some(idx_front) => idx_front,
+                None => continue,
+            };
+
+            // check if the car in front is lapped
+            if race_progs[idx_front] >= race_progs[i]
+                || self.delta_ts_front[i] > blue_flag_pars.blue_flag_window
+            {
+                continue;
+            }
+
+            match self.blue_flags[idx_front].as_mut() {
+                None => {
+                    self.blue_flags[idx_front] = Some(BlueFlag {
+                        idx_lapping: i,
+                        race_prog_shown: race_progs[idx_front],
+                        yielded: false,
+                    })
+                }
+                Some(blue_flag) => {
+                    if blue_flag.idx_lapping == i
+                        && !blue_flag.yielded
+                        && (race_progs[idx_front] - blue_flag.race_prog_shown) * self.track.length
+                            >= blue_flag_pars.s_yield
+                    {
+                        blue_flag.yielded = true;
+                        self.t_overtake_losses[idx_front] +=
+                            self.delta_ts_front[i] + self.min_t_dist + blue_flag_pars.t_yield;
+                    }
+                }
+            }
+        }
+
+        // handle unlapping behind the safety car
+        let sc_in_lap = self.safety_car.as_ref().map(|x| x.in_lap) == Some(true);
+
+        let race_prog_leader = race_progs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.retirement_laps[*i].is_none())
+            .map(|(_, &race_prog)| race_prog)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        for (i, race_prog) in race_progs.iter().enumerate() {
+            if !matches!(self.flag_state, FlagState::Sc)
+                || self.retirement_laps[i].is_some()
+                || self.cars_list[i].sh.pit_act
+            {
+                self.unlapping[i] = None;
+                continue;
+            }
+
+            let deficit = race_prog_leader - race_prog;
+
+            match self.unlapping[i] {
+                // lapped cars start to unlap themselves when the end of the phase is announced
+                None => {
+                    if blue_flag_pars.unlap_behind_sc && sc_in_lap && deficit >= 1.0 {
+                        self.unlapping[i] = Some(Unlapping::Passing(deficit.floor()))
+                    }
+                }
+                // car unlapped itself as soon as it passed the leader
+                Some(Unlapping::Passing(deficit_target)) => {
+                    if deficit < deficit_target {
+                        self.unlapping[i] = Some(Unlapping::CatchingUp)
+                    }
+                }
+                Some(Unlapping::CatchingUp) => {}
+            }
+        }
+    }
+
+    /// The method applies the team orders to those cars that run nose to tail with their teammate
+    /// in front. If the trailing car is faster by at least t_pace_delta_min, the car in front lets
+    /// it through, i.e. it drops behind its teammate and additionally loses the team order time
+    /// delta of its driver. Swaps are recorded as race events.
+    fn handle_team_orders(&mut self) {
+        if self.team_order_pars.is_none() {
+            return;
+        }
+
+        for i in 0..self.cars_list.len() {
+            if self.overtaking_targets[i].is_some() {
+                continue;
+            }
+
+            let idx_front = match self.idxs_front[i] {
+                Some(idx_front) => idx_front,
+                None => continue,
+            };
+
+            if self.get_team_order(idx_front, i) != Some(TeamOrder::Swap) {
+                continue;
+            }
+
+            self.overtaking_targets[i] = Some(idx_front);
+            self.team_order_swaps[i] = Some(idx_front);
+            self.t_overtake_losses[idx_front] += self.delta_ts_front[i]
+                + self.min_t_dist
+                + self.cars_list[idx_front].driver.t_teamorder;
+
+            self.race_events.push(RaceEvent {
+                t_race: self.cur_racetime,
+                lap: self.cars_list[idx_front].sh.get_compl_lap() + 1,
+                car_no: Some(self.cars_list[idx_front].car_no),
+                kind: RaceEventKind::TeamOrderSwap(self.cars_list[i].car_no),
+            });
+        }
+    }
+
+    /// The method evaluates the overtaking attempts of the cars in the overtaking zones. A car
+    /// attempts to overtake the car in front once per overtaking zone if the gap is within the DRS
+    /// window. The success probability increases with the advantage of the attacking car (pace
+    /// delta, DRS, top speed difference) in relation to the overtaking difficulty of the track.
+    /// After a successful attempt, the attacking car is allowed to pass and the attacked car drops
+    /// behind it (it loses the gap plus the minimum distance). After a failed attempt, both cars
+    /// duel and the attacking car must stay behind. Since the attempts are drawn from the random
+    /// number generator of the race, the results are deterministic for a given seed. Without an
+    /// overtaking difficulty, no attempts are evaluated since the cars are free to overtake in the
+    /// overtaking zones (see get_overtaking_allowed).
+    fn handle_overtaking_attempts(&mut self) {
+        if self.track.overtaking_difficulty.is_none() {
+            return;
+        }
+
+        let fcy_active = matches!(
+            self.flag_state,
+            FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+        );
+
+        for i in 0..self.cars_list.len() {
+            // finish or cancel an overtaking maneuver that is in progress
+            if let Some(idx_front) = self.overtaking_targets[i] {
+                if self.retirement_laps[i].is_some()
+                    || self.retirement_laps[idx_front].is_some()
+                    || self.cars_list[i].sh.pit_act
+                    || self.cars_list[idx_front].sh.pit_act
+                    || self.cars_list[i].sh.get_race_prog()
+                        > self.cars_list[idx_front].sh.get_race_prog()
+                    || fcy_active
+                {
+                    self.overtaking_targets[i] = None;
+                    self.team_order_swaps[i] = None;
+                }
+            }
+
+            // attempts are only possible in the overtaking zones (not at the race start) and reset
+            // when leaving a zone
+            let car = &self.cars_list[i];
+
+            if !car.sh.overtaking_act || car.sh.start_act || self.retirement_laps[i].is_some() {
+                self.overtaking_attempts[i] = None;
+                continue;
+            }
+
+            if self.overtaking_targets[i].is_some() {
+                continue;
+            }
+
+            // check if the car closed on the car in front and did not attack it in this zone yet
+            let idx_front = match self.idxs_front[i] {
+                Some(idx_front) => idx_front,
+                None => continue,
+            };
+
+            if self.delta_ts_front[i] > self.drs_window
+                || self.overtaking_attempts[i] == Some(idx_front)
+                || self.get_team_order(idx_front, i) == Some(TeamOrder::Hold)
+            {
+                continue;
+            }
+
+            self.overtaking_attempts[i] = Some(idx_front);
+
+            // draw the outcome of the attempt
+            let p_overtake = self.calc_overtaking_prob(idx_front, i);
+
+            if self.rng.gen::<f64>() < p_overtake {
+                self.overtaking_targets[i] = Some(idx_front);
+                self.t_overtake_losses[idx_front] += self.delta_ts_front[i] + self.min_t_dist;
+            } else {
+                self.cars_list[i].sh.duel_act = true;
+
+                if self.cars_list[idx_front].sh.overtaking_act {
+                    self.cars_list[idx_front].sh.duel_act = true;
+                }
+            }
         }
     }
 
@@ -540,8 +2250,77 @@ impl Race {
     // METHODS (HELPERS) ---------------------------------------------------------------------------
     // ---------------------------------------------------------------------------------------------
 
+    /// get_team_order returns the team order that applies to the inserted pair of cars (None if
+    /// the cars do not belong to the same team or if no team order applies in the current
+    /// situation). Team orders are only given under green flag within the lap window if the gap
+    /// between the cars is within max_gap.
+    fn get_team_order(&self, idx_front: usize, idx_rear: usize) -> Option<TeamOrder> {
+        let team_order_pars = self.team_order_pars.as_ref()?;
+        let car_front = &self.cars_list[idx_front];
+        let car_rear = &self.cars_list[idx_rear];
+
+        let cur_lap = car_rear.sh.get_compl_lap() + 1;
+
+        if car_front.team != car_rear.team
+            || cur_lap < team_order_pars.lap_window[0]
+            || cur_lap > team_order_pars.lap_window[1]
+            || self.delta_ts_front[idx_rear] > team_order_pars.max_gap
+            || self.retirement_laps[idx_front].is_some()
+            || self.retirement_laps[idx_rear].is_some()
+            || car_front.sh.pit_act
+            || car_rear.sh.pit_act
+            || car_rear.sh.start_act
+            || !matches!(self.flag_state, FlagState::G | FlagState::Y)
+        {
+            return None;
+        }
+
+        // the teams judge the pace of their cars without the random lap time variation
+        let t_pace_delta = car_front.calc_basic_timeloss(self.track.s_mass)
+            - car_rear.calc_basic_timeloss(self.track.s_mass);
+
+        if t_pace_delta >= team_order_pars.t_pace_delta_min {
+            Some(TeamOrder::Swap)
+        } else if team_order_pars.hold_position {
+            Some(TeamOrder::Hold)
+        } else {
+            None
+        }
+    }
+
+    /// calc_overtaking_prob calculates the probability that the rear car successfully overtakes
+    /// the front car. The advantage of the rear car is the sum of the pace delta (theoretical lap
+    /// times), the DRS effect (if only the rear car uses DRS), and the top speed difference. The
+    /// probability is 1 - exp(-advantage / overtaking_difficulty), and zero without an advantage.
+    fn calc_overtaking_prob(&self, idx_front: usize, idx_rear: usize) -> f64 {
+        let car_front = &self.cars_list[idx_front];
+        let car_rear = &self.cars_list[idx_rear];
+
+        let t_pace = self.cur_th_laptimes[idx_front] - self.cur_th_laptimes[idx_rear];
+
+        let mut t_drs = 0.0;
+
+        if car_rear.sh.drs_act {
+            t_drs -= self.track.t_drseffect
+        }
+
+        if car_front.sh.drs_act {
+            t_drs += self.track.t_drseffect
+        }
+
+        let t_vel_max = (car_rear.driver.vel_max - car_front.driver.vel_max) * self.track.k_vel_max;
+        let t_advantage = t_pace + t_drs + t_vel_max;
+
+        match self.track.overtaking_difficulty {
+            Some(overtaking_difficulty) if t_advantage > 0.0 => {
+                1.0 - (-t_advantage / overtaking_difficulty).exp()
+            }
+            _ => 0.0,
+        }
+    }
+
     /// get_car_order_on_track returns the indices of the cars on the track in the correct order
-    /// (sorted by descending s coordinate).
+    /// (sorted by descending s coordinate). Retired cars are not part of the list.
     fn get_car_order_on_track(&self) -> Vec<usize> {
         // get s coordinates
         let s_tracks_cur: Vec<f64> = self
@@ -550,8 +2329,11 @@ impl Race {
             .map(|car| car.sh.get_s_tracks().1)
             .collect();
 
-        // get indices that sort the vector in a descending order
+        // get indices that sort the vector in a descending order and remove retired cars
         argsort(&s_tracks_cur, SortOrder::Descending)
+            .into_iter()
+            .filter(|&idx| self.retirement_laps[idx].is_none())
+            .collect()
     }
 
     /// calc_projected_delta_t calculates the temporal distance between two cars, i.e. the time it
@@ -569,6 +2351,30 @@ impl Race {
         delta_lap_frac * self.cur_laptimes[idx_rear]
     }
 
+    /// calc_projected_delta_t_sc calculates the temporal distance between the safety car and the
+    /// car behind it analogously to calc_projected_delta_t.
+    fn calc_projected_delta_t_sc(&self, idx_rear: usize, timestep_size: f64) -> f64 {
+        let safety_car = self
+            .safety_car
+            .as_ref()
+            .expect("Safety car is not on the track!");
+
+        // get lap fractions and virtually increase race progress
+        let mut lap_frac_cur_front = safety_car.get_lap_frac() + timestep_size / safety_car.laptime;
+        let mut lap_frac_cur_rear = self.cars_list[idx_rear].sh.get_lap_fracs().1
+            + timestep_size / self.cur_laptimes[idx_rear];
+
+        if lap_frac_cur_front >= 1.0 {
+            lap_frac_cur_front -= 1.0
+        }
+
+        if lap_frac_cur_rear >= 1.0 {
+            lap_frac_cur_rear -= 1.0
+        }
+
+        calc_delta_lap_frac(lap_frac_cur_front, lap_frac_cur_rear) * self.cur_laptimes[idx_rear]
+    }
+
     /// calc_projected_delta_lap_frac calculates the spatial distance between two cars considering
     /// lap fraction, no complete laps. If timestep_size is greater than 0.0, both cars are
     /// projected for that time step into the future before calculating their spatial distance
@@ -596,11 +2402,7 @@ impl Race {
         }
 
         // calculate spatial distance between the two cars
-        if lap_frac_cur_front >= lap_frac_cur_rear {
-            lap_frac_cur_front - lap_frac_cur_rear
-        } else {
-            lap_frac_cur_front + 1.0 - lap_frac_cur_rear
-        }
+        calc_delta_lap_frac(lap_frac_cur_front, lap_frac_cur_rear)
     }
 
     /// get_car_pair_idxs_list creates a list of car index pairs (idx front, idx rear) that can be
@@ -621,14 +2423,73 @@ impl Race {
         car_pair_idxs_list
     }
 
-    /// get_all_finished checks if all race participants have finished the race.
+    /// get_all_finished checks if all race participants have finished the race (or retired).
     pub fn get_all_finished(&self) -> bool {
-        self.race_finished.iter().all(|&x| x)
+        self.race_finished
+            .iter()
+            .zip(self.retirement_laps.iter())
+            .all(|(&finished, retirement_lap)| finished || retirement_lap.is_some())
+    }
+
+    /// get_retired checks if the car at the inserted index retired from the race.
+    pub fn get_retired(&self, idx: usize) -> bool {
+        self.retirement_laps[idx].is_some()
+    }
+
+    /// get_telemetry returns the recorded telemetry (if the recording was activated).
+    pub fn get_telemetry(&self) -> Option<&Telemetry> {
+        self.telemetry.as_ref()
     }
 
     /// get_race_result returns a race result struct of the race.
     pub fn get_race_result(&self) -> RaceResult {
+        // sort race events by their race times (events are drawn in advance for the current lap,
+        // i.e. events that were not reached before the end of the race are removed)
+        let mut race_events_sorted = self.race_events.to_owned();
+        race_events_sorted.retain(|x| x.t_race <= self.cur_racetime);
+        race_events_sorted.sort_by(|a, b| a.t_race.partial_cmp(&b.t_race).unwrap());
+
+        // check the tire sets used during the race for violations of the regulations
+        let mut regulation_violations = vec![];
+        let mut t_penalties = vec![0.0; self.cars_list.len()];
+
+        if self.regulation_pars.enforcement == Enforcement::Penalty {
+            for (i, car) in self.cars_list.iter().enumerate() {
+                let violations = self.regulations.check_tiresets(
+                    car.get_tiresets_used(),
+                    car.tiresets_available.as_deref(),
+                    self.retirement_laps[i].is_none(),
+                );
+
+                for kind in violations.into_iter() {
+                    t_penalties[i] += self.regulation_pars.t_penalty;
+                    regulation_violations.push(RegulationViolation {
+                        car_no: car.car_no,
+                        kind,
+                        t_penalty: self.regulation_pars.t_penalty,
+                    })
+                }
+            }
+        }
+
+        // add the penalties that were not served during the race to the final race times
+        let mut penalties = vec![];
+
+        for (i, car) in self.cars_list.iter().enumerate() {
+            for penalty in car.get_penalties().iter() {
+                let mut penalty = penalty.to_owned();
+
+                if penalty.lap_served.is_none() {
+                    penalty.t_added = penalty.penalty_type.get_t_penalty(&self.penalty_pars);
+                    t_penalties[i] += penalty.t_added;
+                }
+
+                penalties.push(penalty)
+            }
+        }
+
         RaceResult {
+            seed: self.seed,
             tot_no_laps: self.tot_no_laps,
             car_driver_pairs: self
                 .cars_list
@@ -640,6 +2501,501 @@ impl Race {
                 .collect(),
             laptimes: self.laptimes.to_owned(),
             racetimes: self.racetimes.to_owned(),
+            compounds: self.compounds.to_owned(),
+            sectortimes: self.sectortimes.to_owned(),
+            drs_point_times: self.drs_point_times.to_owned(),
+            retirement_laps: self.retirement_laps.to_owned(),
+            race_events: race_events_sorted,
+            regulation_violations,
+            penalties,
+            pit_stops: self.pit_stops.to_owned(),
+            t_penalties,
+        }
+    }
+}
+
+/// determine_grid_positions returns the grid positions of the cars at the race start on the basis
+/// of their qualifying positions and grid penalties (None for cars that start from the pit lane).
+/// The cars without grid penalties keep the order of their qualifying positions and move up to fill
+/// the gaps. Cars with a place penalty are then inserted at their qualifying position plus the
+/// penalty (at most at the end of the grid). If several penalized cars end up at the same position,
+/// the car with the better qualifying position starts in front. The cars that start from the back
+/// of the grid follow at the end.
+fn determine_grid_positions(
+    p_grids: &[u32],
+    grid_penalties: &[Option<GridPenalty>],
+) -> Vec<Option<u32>> {
+    let mut idxs_grid: Vec<usize> = (0..p_grids.len())
+        .filter(|&idx| grid_penalties[idx].is_none())
+        .collect();
+    idxs_grid.sort_by_key(|&idx| p_grids[idx]);
+
+    // insert cars with a place penalty (sorted by their target positions, a car cannot be inserted
+    // in front of a car that was inserted before)
+    let mut idxs_places: Vec<(u32, usize)> = grid_penalties
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, grid_penalty)| match grid_penalty {
+            Some(GridPenalty::Places(places)) => Some((p_grids[idx] + places, idx)),
+            _ => None,
+        })
+        .collect();
+    idxs_places.sort_by_key(|&(p_target, idx)| (p_target, p_grids[idx]));
+
+    let mut pos_min = 0;
+
+    for (p_target, idx) in idxs_places.into_iter() {
+        let pos = (p_target as usize - 1).max(pos_min).min(idxs_grid.len());
+        idxs_grid.insert(pos, idx);
+        pos_min = pos + 1;
+    }
+
+    // append cars that start from the back of the grid
+    let mut idxs_back: Vec<usize> = (0..p_grids.len())
+        .filter(|&idx| grid_penalties[idx] == Some(GridPenalty::BackOfGrid))
+        .collect();
+    idxs_back.sort_by_key(|&idx| p_grids[idx]);
+    idxs_grid.append(&mut idxs_back);
+
+    let mut grid_positions = vec![None; p_grids.len()];
+
+    for (i, &idx) in idxs_grid.iter().enumerate() {
+        grid_positions[idx] = Some(i as u32 + 1);
+    }
+
+    grid_positions
+}
+
+/// calc_delta_lap_frac calculates the spatial distance (as lap fraction) between a front and a rear
+/// object on the track on the basis of their lap fractions.
+fn calc_delta_lap_frac(lap_frac_front: f64, lap_frac_rear: f64) -> f64 {
+    if lap_frac_front >= lap_frac_rear {
+        lap_frac_front - lap_frac_rear
+    } else {
+        lap_frac_front + 1.0 - lap_frac_rear
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        determine_grid_positions, BlueFlag, FcyPhase, FcyPhaseType, FlagState, Race, Unlapping,
+    };
+    use crate::core::car::GridPenalty;
+    use crate::core::events::RaceEventKind;
+    use crate::core::penalties::PenaltyType;
+    use crate::core::pit_stops::PitLanePars;
+    use crate::post::race_result::ClassificationStatus;
+    use crate::pre::read_sim_pars::{read_example_sim_pars, SimPars};
+
+    fn create_race(sim_pars: &SimPars, seed: u64) -> Race {
+        Race::new(
+            &sim_pars.race_pars,
+            &sim_pars.track_pars,
+            &sim_pars.driver_pars_all,
+            &sim_pars.car_pars_all,
+            0.2,
+            seed,
+        )
+    }
+
+    /// place_cars lets car j pass car i on the track in the current time step (both cars in their
+    /// first lap after the race start).
+    fn place_cars(race: &mut Race, i: usize, j: usize) {
+        for car in race.cars_list.iter_mut() {
+            car.sh.start_act = false;
+        }
+        race.race_progs_prev[i] = 0.5;
+        race.race_progs_prev[j] = 0.4;
+        race.cars_list[i].sh.set_s_track(1000.0);
+        race.cars_list[j].sh.set_s_track(1010.0);
+    }
+
+    /// simulate_race simulates the race until all cars finished (or retired).
+    fn simulate_race(sim_pars: &SimPars, seed: u64) -> Race {
+        let mut race = create_race(sim_pars, seed);
+
+        while !race.get_all_finished() {
+            race.simulate_timestep();
+        }
+
+        race
+    }
+
+    /// simulate_pit_stops lets the inserted cars pit at the end of the first lap (with pit lane
+    /// interaction) and simulates the race until all of them left the pit lane again.
+    fn simulate_pit_stops(car_nos: &[u32], t_release_gap: f64, p_unsafe_release: f64) -> Race {
+        let mut sim_pars = read_example_sim_pars();
+        sim_pars.race_pars.pit_lane_pars = Some(PitLanePars {
+            t_release_gap,
+            p_unsafe_release,
+            penalty_unsafe_release: PenaltyType::Time5,
+        });
+
+        for car_no in car_nos {
+            sim_pars.car_pars_all.get_mut(car_no).unwrap().strategy[1].inlap = 1;
+        }
+
+        let mut race = create_race(&sim_pars, 0);
+
+        while race.cur_racetime < 250.0 {
+            race.simulate_timestep();
+        }
+
+        race
+    }
+
+    #[test]
+    fn test_grid_positions_no_penalties() {
+        let p_grids = vec![2, 1, 3];
+        let grid_penalties = vec![None, None, None];
+        assert_eq!(
+            determine_grid_positions(&p_grids, &grid_penalties),
+            vec![Some(2), Some(1), Some(3)]
+        );
+    }
+    #[test]
+    fn test_grid_positions_places() {
+        let p_grids = vec![1, 2, 3, 4, 5];
+        let grid_penalties = vec![Some(GridPenalty::Places(2)), None, None, None, None];
+        assert_eq!(
+            determine_grid_positions(&p_grids, &grid_penalties),
+            vec![Some(3), Some(1), Some(2), Some(4), Some(5)]
+        );
+    }
+    #[test]
+    fn test_grid_positions_places_beyond_grid() {
+        let p_grids = vec![1, 2, 3, 4, 5];
+        let grid_penalties = vec![None, None, None, Some(GridPenalty::Places(10)), None];
+        assert_eq!(
+            determine_grid_positions(&p_grids, &grid_penalties),
+            vec![Some(1), Some(2), Some(3), Some(5), Some(4)]
+        );
+    }
+    #[test]
+    fn test_grid_positions_places_collision() {
+        // both penalized cars target position 3, the better qualified car starts in front
+        let p_grids = vec![1, 2, 3, 4, 5];
+        let grid_penalties = vec![
+            Some(GridPenalty::Places(2)),
+            Some(GridPenalty::Places(1)),
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(
+            determine_grid_positions(&p_grids, &grid_penalties),
+            vec![Some(3), Some(4), Some(1), Some(2), Some(5)]
+        );
+    }
+    #[test]
+    fn test_grid_positions_back_of_grid_and_pitlane_start() {
+        let p_grids = vec![1, 2, 3, 4, 5];
+        let grid_penalties = vec![
+            Some(GridPenalty::BackOfGrid),
+            Some(GridPenalty::PitlaneStart),
+            Some(GridPenalty::Places(5)),
+            None,
+            Some(GridPenalty::BackOfGrid),
+        ];
+        assert_eq!(
+            determine_grid_positions(&p_grids, &grid_penalties),
+            vec![Some(3), None, Some(2), Some(1), Some(4)]
+        );
+    }
+
+    /// set_advantage places car 1 directly behind car 0 (after the race start) and sets the pace
+    /// advantage of car 1 as well as the overtaking difficulty of the track.
+    fn set_advantage(race: &mut Race, t_advantage: f64, overtaking_difficulty: f64) {
+        for car in race.cars_list.iter_mut() {
+            car.sh.start_act = false;
+            car.sh.drs_act = false;
+        }
+        race.track.k_vel_max = 0.0;
+        race.track.overtaking_difficulty = Some(overtaking_difficulty);
+        race.cur_th_laptimes[0] = 100.0 + t_advantage;
+        race.cur_th_laptimes[1] = 100.0;
+        race.idxs_front[1] = Some(0);
+        race.delta_ts_front[1] = 0.5;
+    }
+
+    #[test]
+    fn test_overtaking_prob() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+
+        set_advantage(&mut race, 0.0, 1.0);
+        assert_eq!(race.calc_overtaking_prob(0, 1), 0.0);
+        set_advantage(&mut race, -0.5, 1.0);
+        assert_eq!(race.calc_overtaking_prob(0, 1), 0.0);
+
+        // rising with the advantage
+        set_advantage(&mut race, 0.5, 1.0);
+        let p_small_advantage = race.calc_overtaking_prob(0, 1);
+        set_advantage(&mut race, 1.0, 1.0);
+        let p_big_advantage = race.calc_overtaking_prob(0, 1);
+        assert!(0.0 < p_small_advantage && p_small_advantage < p_big_advantage);
+        assert!((p_big_advantage - (1.0 - (-1.0f64).exp())).abs() < 1e-9);
+
+        // falling with the difficulty
+        set_advantage(&mut race, 1.0, 2.0);
+        assert!(race.calc_overtaking_prob(0, 1) < p_big_advantage);
+    }
+    #[test]
+    fn test_overtaking_attempt_success() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        set_advantage(&mut race, 10.0, 0.5);
+        race.handle_overtaking_attempts();
+
+        assert_eq!(race.overtaking_targets[1], Some(0));
+        assert_eq!(race.overtaking_attempts[1], Some(0));
+        assert_eq!(race.t_overtake_losses[0], 0.5 + race.min_t_dist);
+        assert!(!race.cars_list[1].sh.duel_act);
+    }
+    #[test]
+    fn test_overtaking_attempt_failure() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        set_advantage(&mut race, 0.0, 0.5);
+        race.handle_overtaking_attempts();
+
+        assert_eq!(race.overtaking_targets[1], None);
+        assert_eq!(race.overtaking_attempts[1], Some(0));
+        assert_eq!(race.t_overtake_losses[0], 0.0);
+        assert!(race.cars_list[1].sh.duel_act);
+
+        // no second attempt on the same car in the same overtaking zone
+        set_advantage(&mut race, 10.0, 0.5);
+        race.handle_overtaking_attempts();
+        assert_eq!(race.overtaking_targets[1], None);
+    }
+    #[test]
+    fn test_vsc_gaps_frozen() {
+        let mut sim_pars = read_example_sim_pars();
+        sim_pars.race_pars.p_failure = 0.0;
+        sim_pars.track_pars.p_accident = 0.0;
+        sim_pars.race_pars.fcy_phases = vec![FcyPhase {
+            phase_type: FcyPhaseType::Vsc,
+            t_start: 600.0,
+            t_end: 800.0,
+        }];
+        let mut race = create_race(&sim_pars, 0);
+
+        while race.cur_racetime < 610.0 {
+            race.simulate_timestep();
+        }
+
+        let race_progs_start: Vec<f64> = race
+            .cars_list
+            .iter()
+            .map(|car| car.sh.get_race_prog())
+            .collect();
+        let mut pitted = vec![false; race.cars_list.len()];
+
+        while race.cur_racetime < 790.0 {
+            race.simulate_timestep();
+
+            for (i, car) in race.cars_list.iter().enumerate() {
+                pitted[i] |= car.sh.pit_act;
+            }
+        }
+
+        // every car that did not pit covered the same distance, i.e. the gaps are frozen
+        assert!(pitted.iter().any(|&x| !x));
+
+        let laptime_vsc =
+            (race.track.t_q + race.track.t_gap_racepace) * sim_pars.race_pars.f_laptime_vsc;
+
+        for (i, car) in race.cars_list.iter().enumerate() {
+            if !pitted[i] {
+                assert!(
+                    (car.sh.get_race_prog()
+                        - race_progs_start[i]
+                        - (race.cur_racetime - 610.0) / laptime_vsc)
+                        .abs()
+                        < 1e-6
+                );
+            }
+        }
+    }
+    #[test]
+    fn test_position_change_overtake_loser() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        place_cars(&mut race, 0, 1);
+        race.handle_position_changes();
+        assert_eq!(race.t_overtake_losses[0], race.t_overtake_loser);
+        assert_eq!(race.t_overtake_losses[1], 0.0);
+    }
+    #[test]
+    fn test_position_change_blue_flag_charged_once() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        place_cars(&mut race, 0, 1);
+        race.blue_flags[0] = Some(BlueFlag {
+            idx_lapping: 1,
+            race_prog_shown: 0.4,
+            yielded: true,
+        });
+        race.t_overtake_losses[0] = 1.0; // already charged when yielding in handle_blue_flags
+        race.handle_position_changes();
+        assert_eq!(race.t_overtake_losses[0], 1.0);
+    }
+    #[test]
+    fn test_position_change_unlapping() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        place_cars(&mut race, 0, 1);
+        race.unlapping[1] = Some(Unlapping::CatchingUp);
+        race.handle_position_changes();
+        assert_eq!(race.t_overtake_losses[0], 0.0);
+    }
+    #[test]
+    fn test_position_change_fcy() {
+        let mut race = create_race(&read_example_sim_pars(), 0);
+        place_cars(&mut race, 0, 1);
+        race.flag_state = FlagState::Sc;
+        race.handle_position_changes();
+        assert_eq!(race.t_overtake_losses[0], 0.0);
+        assert_eq!(
+            race.race_events.last().unwrap().car_no,
+            Some(race.cars_list[1].car_no)
+        );
+    }
+    #[test]
+    fn test_pit_double_stacking() {
+        let race = simulate_pit_stops(&[44, 77], 0.0, 0.0);
+        assert_eq!(race.pit_stops.len(), 2);
+
+        // the second Mercedes waits for the first one, i.e. only its standstill time is increased
+        let pit_stop_first = &race.pit_stops[0];
+        let pit_stop_second = &race.pit_stops[1];
+        assert_eq!(pit_stop_first.t_queue, 0.0);
+        assert!(pit_stop_second.t_queue > 0.0);
+        assert!(pit_stop_second.t_standstill >= pit_stop_second.t_queue);
+        assert!(
+            pit_stop_second.t_race + pit_stop_second.t_standstill
+                > pit_stop_first.t_race + pit_stop_first.t_standstill
+        );
+    }
+    #[test]
+    fn test_pit_release_hold() {
+        let race = simulate_pit_stops(&[44, 77, 5, 7, 3, 33], 5.0, 0.0);
+        assert_eq!(race.pit_stops.len(), 6);
+        assert!(race.pit_stops.iter().any(|x| x.t_release_hold > 0.0));
+        assert!(race.pit_stops.iter().all(|x| !x.unsafe_release));
+        assert!(!race
+            .race_events
+            .iter()
+            .any(|x| matches!(x.kind, RaceEventKind::UnsafeRelease)));
+    }
+    #[test]
+    fn test_pit_unsafe_release() {
+        let race = simulate_pit_stops(&[44, 77, 5, 7, 3, 33], 5.0, 1.0);
+        assert!(race.pit_stops.iter().all(|x| x.t_release_hold == 0.0));
+
+        let car_nos_unsafe: Vec<u32> = race
+            .pit_stops
+            .iter()
+            .filter(|x| x.unsafe_release)
+            .map(|x| x.car_no)
+            .collect();
+        assert!(!car_nos_unsafe.is_empty());
+
+        for car_no in car_nos_unsafe {
+            assert!(race.race_events.iter().any(
+                |x| x.car_no == Some(car_no) && matches!(x.kind, RaceEventKind::UnsafeRelease)
+            ));
+            assert!(race.race_events.iter().any(|x| x.car_no == Some(car_no)
+                && matches!(x.kind, RaceEventKind::Penalty(PenaltyType::Time5))));
+        }
+    }
+    #[test]
+    fn test_events_reproducible() {
+        let mut sim_pars = read_example_sim_pars();
+        sim_pars.race_pars.p_failure = 0.005;
+        sim_pars.track_pars.p_accident = 0.005;
+
+        let race_a = simulate_race(&sim_pars, 1);
+        let race_b = simulate_race(&sim_pars, 1);
+
+        assert!(race_a
+            .race_events
+            .iter()
+            .any(|x| matches!(x.kind, RaceEventKind::Accident | RaceEventKind::Failure)));
+        assert_eq!(
+            serde_json::to_string(&race_a.race_events).unwrap(),
+            serde_json::to_string(&race_b.race_events).unwrap()
+        );
+        assert_eq!(race_a.retirement_laps, race_b.retirement_laps);
+        assert!(race_a.retirement_laps.iter().any(|x| x.is_some()));
+    }
+    #[test]
+    fn test_forced_retirements_classification() {
+        // car 7 retires in the last lap (classified), car 33 in lap 10 and car 3 in the first lap
+        // (both not classified)
+        let mut sim_pars = read_example_sim_pars();
+        sim_pars.car_pars_all.get_mut(&7).unwrap().dnf_lap = Some(55);
+        sim_pars.car_pars_all.get_mut(&33).unwrap().dnf_lap = Some(10);
+        sim_pars.car_pars_all.get_mut(&3).unwrap().dnf_lap = Some(1);
+
+        let race_result = simulate_race(&sim_pars, 0).get_race_result();
+        let idx = |car_no: u32| {
+            race_result
+                .car_driver_pairs
+                .iter()
+                .position(|x| x.car_no == car_no)
+                .unwrap()
+        };
+
+        for (car_no, retirement_lap) in [(7, Some(55)), (33, Some(10)), (3, Some(1)), (44, None)] {
+            assert_eq!(race_result.retirement_laps[idx(car_no)], retirement_lap);
+            assert_eq!(
+                race_result.get_compl_laps(idx(car_no)),
+                retirement_lap.map_or(55, |x| x - 1)
+            );
+        }
+
+        let classification = race_result.get_final_classification();
+        let car_nos: Vec<u32> = classification.iter().map(|x| x.car_no).collect();
+        let statuses: Vec<ClassificationStatus> =
+            classification.iter().map(|x| x.status.to_owned()).collect();
+
+        assert_eq!(car_nos[3..], [7, 33, 3]);
+        assert_eq!(
+            statuses,
+            vec![
+                ClassificationStatus::Finished,
+                ClassificationStatus::Finished,
+                ClassificationStatus::Finished,
+                ClassificationStatus::Retired,
+                ClassificationStatus::NotClassified,
+                ClassificationStatus::NotClassified,
+            ]
+        );
+        assert_eq!(
+            race_result
+                .race_events
+                .iter()
+                .filter(|x| matches!(x.kind, RaceEventKind::Retirement))
+                .count(),
+            3
+        );
+    }
+    #[test]
+    fn test_sector_times() {
+        let race_result = simulate_race(&read_example_sim_pars(), 0).get_race_result();
+
+        for i in 0..race_result.car_driver_pairs.len() {
+            for lap in 1..=race_result.tot_no_laps as usize {
+                // every sector time is positive and the sector times sum up to the lap time
+                let sectortimes = race_result.sectortimes[i][lap];
+                assert!(sectortimes.iter().all(|&x| x > 0.0));
+                assert!(
+                    (sectortimes.iter().sum::<f64>() - race_result.laptimes[i][lap]).abs() < 1e-9
+                );
+
+                // the DRS measurement points are passed within the lap
+                for &t_passed in race_result.drs_point_times[i][lap].iter() {
+                    assert!(race_result.racetimes[i][lap - 1] <= t_passed);
+                    assert!(t_passed <= race_result.racetimes[i][lap]);
+                }
+            }
         }
     }
 }
@@ -0,0 +1,319 @@
+use crate::core::tireset::{TireCategory, TiresetPars};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Enforcement {
+    Validation, // planned strategies are checked before the race, violations raise an error
+    Penalty,    // driven strategies are checked after the race, violations lead to time penalties
+    Ignore,     // strategies are not checked
+}
+
+/// * `enforcement` - Handling of regulation violations -> validation (the planned strategies are
+/// checked before the race and a violation raises an error), penalty (the strategies that were
+/// actually driven are checked after the race, e.g. after adaptations by a strategy controller,
+/// and a violation leads to a time penalty), ignore (the strategies are not checked, default)
+/// * `t_penalty` - (s) Time penalty per violation that is added to the final race time (penalty
+/// enforcement only)
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegulationPars {
+    pub enforcement: Enforcement,
+    pub t_penalty: f64,
+}
+
+impl Default for RegulationPars {
+    fn default() -> Self {
+        RegulationPars {
+            enforcement: Enforcement::Ignore,
+            t_penalty: 0.0,
+        }
+    }
+}
+
+/// TireAllocation contains the number of tire sets per category that are available to a driver
+/// for a race weekend.
+#[derive(Debug, Clone)]
+pub struct TireAllocation {
+    pub no_sets_dry: u32,
+    pub no_sets_intermediate: u32,
+    pub no_sets_wet: u32,
+}
+
+impl TireAllocation {
+    /// The method returns the number of available tire sets of the inserted category.
+    pub fn get_no_sets(&self, tire_category: TireCategory) -> u32 {
+        match tire_category {
+            TireCategory::Dry => self.no_sets_dry,
+            TireCategory::Intermediate => self.no_sets_intermediate,
+            TireCategory::Wet => self.no_sets_wet,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    CompoundRule,                // too few different dry compounds used in a dry race
+    TireAllocation(TiresetPars), // tire set used that was not available for the race
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ViolationKind::CompoundRule => write!(f, "compound rule not fulfilled"),
+            ViolationKind::TireAllocation(tireset) => write!(
+                f,
+                "tire set {} (age {} laps) not available",
+                tireset.compound, tireset.age
+            ),
+        }
+    }
+}
+
+/// RegulationViolation is used to record a violation of the regulations and the resulting time
+/// penalty in the race result.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegulationViolation {
+    pub car_no: u32,
+    pub kind: ViolationKind,
+    pub t_penalty: f64,
+}
+
+/// Regulations contains the (simplified) sporting regulations of a season that concern the use of
+/// tires during the race.
+///
+/// * `season` - Season the regulations belong to
+/// * `min_no_dry_compounds` - Minimum number of different dry compounds that must be used during
+/// a race (1 if there is no such rule)
+/// * `wet_exemption` - True if the compound rule does not apply if intermediate or wet tires were
+/// used during the race
+/// * `tire_allocation` - Tire sets per race weekend (None if not regulated)
+#[derive(Debug, Clone)]
+pub struct Regulations {
+    pub season: u32,
+    pub min_no_dry_compounds: usize,
+    pub wet_exemption: bool,
+    pub tire_allocation: Option<TireAllocation>,
+}
+
+impl Regulations {
+    pub fn new(season: u32) -> Regulations {
+        match season {
+            // competition between several tire suppliers without compound rule and tire allocation
+            0..=2006 => Regulations {
+                season,
+                min_no_dry_compounds: 1,
+                wet_exemption: true,
+                tire_allocation: None,
+            },
+            // single tire supplier, introduction of the compound rule (refueling still allowed)
+            2007..=2009 => Regulations {
+                season,
+                min_no_dry_compounds: 2,
+                wet_exemption: true,
+                tire_allocation: Some(TireAllocation {
+                    no_sets_dry: 14,
+                    no_sets_intermediate: 4,
+                    no_sets_wet: 3,
+                }),
+            },
+            // refueling ban, reduced tire allocation
+            2010..=2015 => Regulations {
+                season,
+                min_no_dry_compounds: 2,
+                wet_exemption: true,
+                tire_allocation: Some(TireAllocation {
+                    no_sets_dry: 11,
+                    no_sets_intermediate: 4,
+                    no_sets_wet: 3,
+                }),
+            },
+            // increased tire allocation (three compounds per race weekend)
+            _ => Regulations {
+                season,
+                min_no_dry_compounds: 2,
+                wet_exemption: true,
+                tire_allocation: Some(TireAllocation {
+                    no_sets_dry: 13,
+                    no_sets_intermediate: 4,
+                    no_sets_wet: 3,
+                }),
+            },
+        }
+    }
+
+    /// The method checks if the inserted compounds (used during a race) fulfill the compound rule.
+    pub fn fulfills_compound_rule(&self, compounds: &[String]) -> bool {
+        if self.wet_exemption
+            && compounds
+                .iter()
+                .any(|x| TireCategory::from_compound(x) != TireCategory::Dry)
+        {
+            return true;
+        }
+
+        self.get_no_dry_compounds(compounds) >= self.min_no_dry_compounds
+    }
+
+    /// The method returns the number of different dry compounds within the inserted compounds.
+    pub fn get_no_dry_compounds(&self, compounds: &[String]) -> usize {
+        let mut dry_compounds: Vec<&String> = compounds
+            .iter()
+            .filter(|x| TireCategory::from_compound(x) == TireCategory::Dry)
+            .collect();
+        dry_compounds.sort();
+        dry_compounds.dedup();
+        dry_compounds.len()
+    }
+
+    /// The method checks if the inserted tire sets available for the race fit into the tire
+    /// allocation of the race weekend. It returns the first category that exceeds the allocation.
+    pub fn check_tire_allocation(
+        &self,
+        tiresets_available: &[TiresetPars],
+    ) -> Option<TireCategory> {
+        let tire_allocation = self.tire_allocation.as_ref()?;
+
+        [
+            TireCategory::Dry,
+            TireCategory::Intermediate,
+            TireCategory::Wet,
+        ]
+        .iter()
+        .find(|&&tire_category| {
+            tiresets_available
+                .iter()
+                .filter(|x| TireCategory::from_compound(&x.compound) == tire_category)
+                .count() as u32
+                > tire_allocation.get_no_sets(tire_category)
+        })
+        .copied()
+    }
+
+    /// The method checks the inserted tire sets (in the order they are used during the race) for
+    /// violations of the regulations. Every tire set must be assigned to a different available set
+    /// with the same compound and age (if the available sets are given). The compound rule is only
+    /// checked if check_compound_rule is true, e.g. it does not apply to retired cars.
+    pub fn check_tiresets(
+        &self,
+        tiresets_used: &[TiresetPars],
+        tiresets_available: Option<&[TiresetPars]>,
+        check_compound_rule: bool,
+    ) -> Vec<ViolationKind> {
+        let mut violations = vec![];
+
+        if check_compound_rule {
+            let compounds: Vec<String> = tiresets_used
+                .iter()
+                .map(|x| x.compound.to_owned())
+                .collect();
+
+            if !self.fulfills_compound_rule(&compounds) {
+                violations.push(ViolationKind::CompoundRule);
+            }
+        }
+
+        if let Some(tiresets_available) = tiresets_available {
+            let mut assigned = vec![false; tiresets_available.len()];
+
+            for tireset in tiresets_used.iter() {
+                let idx = tiresets_available
+                    .iter()
+                    .enumerate()
+                    .position(|(i, x)| !assigned[i] && x == tireset);
+
+                match idx {
+                    Some(idx) => assigned[idx] = true,
+                    None => violations.push(ViolationKind::TireAllocation(tireset.to_owned())),
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Regulations, ViolationKind};
+    use crate::core::tireset::TiresetPars;
+
+    fn compounds(x: &[&str]) -> Vec<String> {
+        x.iter().map(|x| x.to_string()).collect()
+    }
+
+    fn tiresets(x: &[(&str, u32)]) -> Vec<TiresetPars> {
+        x.iter()
+            .map(|&(compound, age)| TiresetPars {
+                compound: compound.to_string(),
+                age,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compound_rule_2006() {
+        let regulations = Regulations::new(2006);
+        assert!(regulations.fulfills_compound_rule(&compounds(&["A3", "A3"])));
+        assert!(regulations.tire_allocation.is_none());
+    }
+    #[test]
+    fn test_compound_rule_2009() {
+        let regulations = Regulations::new(2009);
+        assert!(!regulations.fulfills_compound_rule(&compounds(&["A3", "A3"])));
+        assert!(regulations.fulfills_compound_rule(&compounds(&["A3", "A4"])));
+        assert_eq!(regulations.tire_allocation.unwrap().no_sets_dry, 14);
+    }
+    #[test]
+    fn test_compound_rule_2015() {
+        let regulations = Regulations::new(2015);
+        assert!(!regulations.fulfills_compound_rule(&compounds(&["A3"])));
+        assert!(regulations.fulfills_compound_rule(&compounds(&["A3", "A4", "A3"])));
+        assert_eq!(regulations.tire_allocation.unwrap().no_sets_dry, 11);
+    }
+    #[test]
+    fn test_compound_rule_2017() {
+        let regulations = Regulations::new(2017);
+        assert!(!regulations.fulfills_compound_rule(&compounds(&["A2", "A2"])));
+        assert!(regulations.fulfills_compound_rule(&compounds(&["A2", "A3"])));
+        assert_eq!(regulations.tire_allocation.unwrap().no_sets_dry, 13);
+    }
+    #[test]
+    fn test_compound_rule_wet_exemption() {
+        let regulations = Regulations::new(2017);
+        assert!(regulations.fulfills_compound_rule(&compounds(&["A2", "I"])));
+        assert!(regulations.fulfills_compound_rule(&compounds(&["W"])));
+    }
+    #[test]
+    fn test_check_tiresets_no_violations() {
+        let regulations = Regulations::new(2017);
+        let available = tiresets(&[("A2", 0), ("A3", 2), ("A3", 0)]);
+        let used = tiresets(&[("A3", 2), ("A2", 0)]);
+        assert!(regulations
+            .check_tiresets(&used, Some(&available), true)
+            .is_empty());
+    }
+    #[test]
+    fn test_check_tiresets_compound_rule() {
+        let regulations = Regulations::new(2017);
+        let used = tiresets(&[("A3", 2), ("A3", 0)]);
+        let violations = regulations.check_tiresets(&used, None, true);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], ViolationKind::CompoundRule));
+        assert!(regulations.check_tiresets(&used, None, false).is_empty());
+    }
+    #[test]
+    fn test_check_tiresets_allocation() {
+        // the second set with compound A3 and age 0 is not available
+        let regulations = Regulations::new(2017);
+        let available = tiresets(&[("A2", 0), ("A3", 0)]);
+        let used = tiresets(&[("A3", 0), ("A2", 0), ("A3", 0)]);
+        let violations = regulations.check_tiresets(&used, Some(&available), true);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            &violations[0],
+            ViolationKind::TireAllocation(tireset) if tireset.compound == "A3" && tireset.age == 0
+        ));
+    }
+}
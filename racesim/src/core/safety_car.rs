@@ -0,0 +1,50 @@
+/// The SafetyCar is a physical entity on the track during a safety car phase. It enters the track
+/// directly in front of the race leader, drives at a constant (slow) lap time, and leaves the
+/// track through the pit lane at the end of the lap in which the end of the phase was announced.
+/// The cars are not allowed to overtake it and therefore bunch up behind it.
+#[derive(Debug)]
+pub struct SafetyCar {
+    pub laptime: f64,
+    track_length: f64,
+    s_track_prev: f64,
+    s_track_cur: f64,
+    pub in_lap: bool, // true if the end of the phase was announced, i.e. the SC enters the pits
+}
+
+impl SafetyCar {
+    pub fn new(laptime: f64, track_length: f64, s_track_start: f64) -> SafetyCar {
+        SafetyCar {
+            laptime,
+            track_length,
+            s_track_prev: s_track_start,
+            s_track_cur: s_track_start,
+            in_lap: false,
+        }
+    }
+
+    /// update_race_prog moves the safety car forward according to its lap time.
+    pub fn update_race_prog(&mut self, timestep_size: f64) {
+        self.s_track_prev = self.s_track_cur;
+        self.s_track_cur += timestep_size / self.laptime * self.track_length;
+
+        if self.s_track_cur >= self.track_length {
+            self.s_track_cur -= self.track_length;
+        }
+    }
+
+    /// get_s_track_passed_this_step checks if the safety car passed the inserted s coordinate
+    /// within the current time step.
+    pub fn get_s_track_passed_this_step(&self, s_track: f64) -> bool {
+        if self.s_track_prev <= self.s_track_cur {
+            self.s_track_prev < s_track && s_track <= self.s_track_cur
+        } else {
+            // lap transition within the current time step
+            self.s_track_prev < s_track || s_track <= self.s_track_cur
+        }
+    }
+
+    /// get_lap_frac returns the current lap fraction of the safety car.
+    pub fn get_lap_frac(&self) -> f64 {
+        self.s_track_cur / self.track_length
+    }
+}
@@ -1,6 +1,8 @@
+use crate::core::events::RaceEventKind;
 use crate::core::race::FlagState;
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub enum State {
     Racestart,
     NormalZone,
@@ -111,8 +113,8 @@ impl StateHandler {
     /// check_state_transition checks if the car jumps from one state into another. A car-driver
     /// combo is in a duel for the position if it is within an overtaking zone and if delta_t to
     /// the front or rear car is within the DRS window (not during lapping). The method returns the
-    /// lap fraction that is driven under the new state such that it can be considered in the next
-    /// time step.
+    /// kind of the race event caused by the state transition (if any), i.e. a pit entry, a pit
+    /// exit, or a DRS activation.
     #[allow(clippy::too_many_arguments)]
     pub fn check_state_transition(
         &mut self,
@@ -124,7 +126,9 @@ impl StateHandler {
         flag_state: &FlagState,
         cur_lap_leader: u32,
         drs_allowed_lap: u32,
-    ) {
+    ) -> Option<RaceEventKind> {
+        let mut race_event_kind = None;
+
         match self.state {
             // START -------------------------------------------------------------------------------
             State::Racestart => {
@@ -152,16 +156,21 @@ impl StateHandler {
                     self.state = State::Pitlane;
                     self.pit_act = true;
                     self.in_drs_window = false;
+                    race_event_kind = Some(RaceEventKind::PitEntry);
                 } else if self
                     .get_s_track_passed_this_step(self.overtaking_zones[self.act_zone_idx][0])
                 {
                     self.state = State::OvertakingZone;
-                    if !(matches!(flag_state, FlagState::Vsc | FlagState::Sc)) {
+                    if !(matches!(
+                        flag_state,
+                        FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+                    )) {
                         self.overtaking_act = true;
 
                         // check if DRS gets activated
                         if self.use_drs && cur_lap_leader >= drs_allowed_lap && self.in_drs_window {
                             self.drs_act = true;
+                            race_event_kind = Some(RaceEventKind::DrsActivation);
                         }
 
                         // check duelling (not applied in case of lapping)
@@ -183,9 +192,13 @@ impl StateHandler {
                     self.drs_act = false;
                     self.overtaking_act = false;
                     self.duel_act = false;
+                    race_event_kind = Some(RaceEventKind::PitEntry);
                 } else if self
                     .get_s_track_passed_this_step(self.overtaking_zones[self.act_zone_idx][1])
-                    || matches!(flag_state, FlagState::Vsc | FlagState::Sc)
+                    || matches!(
+                        flag_state,
+                        FlagState::Vsc | FlagState::VscEnding | FlagState::Sc
+                    )
                 {
                     self.state = State::NormalZone;
                     self.act_zone_idx = (self.act_zone_idx + 1) % self.overtaking_zones.len(); // set next zone
@@ -204,6 +217,7 @@ impl StateHandler {
                     self.state = state;
                     self.act_zone_idx = act_zone_idx;
                     self.pit_act = false;
+                    race_event_kind = Some(RaceEventKind::PitExit);
                 }
             }
 
@@ -212,6 +226,8 @@ impl StateHandler {
             // be handled within the race class itself
             State::PitStandstill => {}
         }
+
+        race_event_kind
     }
 
     /// act_pit_standstill is used to activate the pit standstill state from within the race class
@@ -227,6 +243,49 @@ impl StateHandler {
         self.t_standstill_target = t_standstill_target;
     }
 
+    /// act_pitlane_start is used to let the car start the race from the pit lane. The car waits in
+    /// standstill at its start position (at the end of the pit lane) until it is released by an
+    /// external method call.
+    pub fn act_pitlane_start(&mut self) {
+        self.state = State::PitStandstill;
+        self.start_act = false;
+        self.overtaking_act = false;
+        self.pit_act = true;
+        self.pit_standstill_act = true;
+        self.t_standstill = 0.0;
+        self.t_standstill_target = f64::INFINITY;
+    }
+
+    /// release_pit_standstill is used to end the standstill of a car that waits in the pit lane
+    /// (e.g. at the race start) from within the race class. The car leaves the standstill within
+    /// the next time step.
+    pub fn release_pit_standstill(&mut self) {
+        if !matches!(self.state, State::PitStandstill) {
+            panic!("Tried to release standstill without being in standstill state!")
+        }
+
+        self.t_standstill_target = self.t_standstill;
+    }
+
+    /// hold_pit_standstill is used to extend the standstill of a car from within the race class such
+    /// that it does not leave the standstill within the current time step.
+    pub fn hold_pit_standstill(&mut self, timestep_size: f64) {
+        if !matches!(self.state, State::PitStandstill) {
+            panic!("Tried to hold standstill without being in standstill state!")
+        }
+
+        self.t_standstill_target = self.t_standstill_target.max(self.t_standstill) + timestep_size;
+    }
+
+    /// get_t_standstill_remaining returns the remaining standstill time of a car in standstill.
+    pub fn get_t_standstill_remaining(&self) -> f64 {
+        if !matches!(self.state, State::PitStandstill) {
+            panic!("Tried to get remaining standstill time without being in standstill state!")
+        }
+
+        (self.t_standstill_target - self.t_standstill).max(0.0)
+    }
+
     /// deact_pit_standstill is used to deactivate the pit standstill state from within the race
     /// class during a pit stop.
     pub fn deact_pit_standstill(&mut self) {
@@ -343,6 +402,17 @@ impl StateHandler {
         (s_track_prev, s_track_cur)
     }
 
+    /// get_s_track returns the s coordinate in the current time step (can be negative at the race
+    /// start).
+    pub fn get_s_track(&self) -> f64 {
+        self.s_track_cur
+    }
+
+    /// get_state returns the current state of the statemachine.
+    pub fn get_state(&self) -> State {
+        self.state
+    }
+
     /// get_compl_lap returns the number of completed race laps in the current time step (zero at
     /// the race start).
     pub fn get_compl_lap(&self) -> u32 {
@@ -0,0 +1,270 @@
+use crate::core::car::StrategyEntry;
+use crate::core::driver::Driver;
+use crate::core::race::FlagState;
+use crate::core::regulations::Regulations;
+use crate::core::tireset::Tireset;
+use crate::core::weather::Weather;
+use serde::Deserialize;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// * `min_tire_age_fcy` - Minimum tire age (laps) for an additional pit stop during a SC/VSC phase
+/// * `min_tire_age_undercut` - Minimum tire age (laps) to respond to the pit stop of a rival
+/// * `min_laps_left` - Minimum number of remaining laps for a reactive pit stop
+/// * `max_gap_undercut` - (s) Maximum gap to a rival (before its pit stop) for which its pit stop
+/// is responded
+#[derive(Debug, Deserialize, Clone)]
+pub struct RuleBasedPars {
+    pub min_tire_age_fcy: u32,
+    pub min_tire_age_undercut: u32,
+    pub min_laps_left: u32,
+    pub max_gap_undercut: f64,
+}
+
+/// StrategyControllerPars determines the strategy controller of a car -> fixed_plan (pit stops as
+/// given in the strategy entries), rule_based (strategy entries are used as a plan that is adapted
+/// during the race).
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "controller_type", rename_all = "snake_case")]
+pub enum StrategyControllerPars {
+    FixedPlan,
+    RuleBased(RuleBasedPars),
+}
+
+/// RivalInfo contains the information about another car that is available to a strategy
+/// controller.
+///
+/// * `car_no` - Car number
+/// * `race_prog` - Race progress (completed laps plus current lap fraction)
+/// * `compound` - Currently mounted tire compound
+/// * `tire_age` - (laps) Current tire age
+/// * `pit_act` - True if the car is currently in the pit lane
+/// * `last_inlap` - In-lap of the last pit stop (None if the car did not pit yet)
+#[derive(Debug, Clone)]
+pub struct RivalInfo {
+    pub car_no: u32,
+    pub race_prog: f64,
+    pub compound: String,
+    pub tire_age: u32,
+    pub pit_act: bool,
+    pub last_inlap: Option<u32>,
+}
+
+/// StrategyInfo contains the information that is available to a strategy controller when it
+/// decides whether the car pits in the current lap.
+///
+/// * `cur_lap` - Current lap of the car (in-lap if the car pits)
+/// * `tot_no_laps` - Total number of laps in the race
+/// * `race_prog` - Race progress of the car (completed laps plus current lap fraction)
+/// * `laptime` - (s) Current theoretical lap time of the car (used to convert gaps into seconds)
+/// * `t_pit_loss` - (s) Estimated time loss of a pit stop (driving through the pit lane and tire
+/// change)
+/// * `compound` - Currently mounted tire compound
+/// * `tire_age` - (laps) Current tire age
+/// * `compounds_used` - Compounds used so far during the race
+/// * `regulations` - Regulations of the season (e.g. compound rule)
+/// * `driver` - Current driver of the car (provides available compounds and degradation parameters)
+/// * `flag_state` - Current flag state
+/// * `wetness` - Current track wetness (0.0 dry, 1.0 fully wet)
+/// * `weather` - Weather model of the race (None for a dry race), e.g. to compare the compounds at
+/// the current wetness
+/// * `rivals` - Information about the other cars that are still in the race
+#[derive(Debug, Clone)]
+pub struct StrategyInfo {
+    pub cur_lap: u32,
+    pub tot_no_laps: u32,
+    pub race_prog: f64,
+    pub laptime: f64,
+    pub t_pit_loss: f64,
+    pub compound: String,
+    pub tire_age: u32,
+    pub compounds_used: Vec<String>,
+    pub regulations: Rc<Regulations>,
+    pub driver: Rc<Driver>,
+    pub flag_state: FlagState,
+    pub wetness: f64,
+    pub weather: Option<Rc<Weather>>,
+    pub rivals: Vec<RivalInfo>,
+}
+
+/// A StrategyController decides about the pit stops of a car during the race. It is consulted
+/// once per lap when the car reaches the pit entry and returns the strategy entry of the pit stop
+/// if the car should pit in the current lap (in-lap must be equal to the current lap), None
+/// otherwise.
+pub trait StrategyController: Debug {
+    fn decide_pit(&mut self, strategy_info: &StrategyInfo) -> Option<StrategyEntry>;
+}
+
+/// create_strategy_controller creates the strategy controller of a car on the basis of the
+/// inserted parameters (fixed plan if no parameters are given) and its strategy entries.
+pub fn create_strategy_controller(
+    strategy_controller_pars: &Option<StrategyControllerPars>,
+    strategy: &[StrategyEntry],
+) -> Box<dyn StrategyController> {
+    match strategy_controller_pars {
+        None | Some(StrategyControllerPars::FixedPlan) => {
+            Box::new(FixedPlanController::new(strategy))
+        }
+        Some(StrategyControllerPars::RuleBased(rule_based_pars)) => {
+            Box::new(RuleBasedController::new(rule_based_pars, strategy))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FIXED PLAN --------------------------------------------------------------------------------------
+// -------------------------------------------------------------------------------------------------
+
+/// FixedPlanController pits exactly in the in-laps given in the strategy entries.
+#[derive(Debug)]
+pub struct FixedPlanController {
+    plan: Vec<StrategyEntry>,
+}
+
+impl FixedPlanController {
+    pub fn new(strategy: &[StrategyEntry]) -> FixedPlanController {
+        FixedPlanController {
+            plan: strategy.iter().skip(1).cloned().collect(),
+        }
+    }
+}
+
+impl StrategyController for FixedPlanController {
+    fn decide_pit(&mut self, strategy_info: &StrategyInfo) -> Option<StrategyEntry> {
+        self.plan
+            .iter()
+            .find(|x| x.inlap == strategy_info.cur_lap)
+            .cloned()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// RULE BASED --------------------------------------------------------------------------------------
+// -------------------------------------------------------------------------------------------------
+
+/// RuleBasedController uses the strategy entries as a plan, which is adapted during the race: the
+/// car pits earlier than planned (or additionally if no stop is left) during a SC/VSC phase, and
+/// it responds to the pit stop of a close rival to avoid being undercut. An early pit stop
+/// replaces the next planned pit stop and fits the planned compound. For an additional pit stop,
+/// the compound with the smallest degradation time loss over the remaining laps is chosen (under
+/// consideration of the two-compound rule).
+#[derive(Debug)]
+pub struct RuleBasedController {
+    min_tire_age_fcy: u32,
+    min_tire_age_undercut: u32,
+    min_laps_left: u32,
+    max_gap_undercut: f64,
+    plan: Vec<StrategyEntry>,
+}
+
+impl RuleBasedController {
+    pub fn new(rule_based_pars: &RuleBasedPars, strategy: &[StrategyEntry]) -> RuleBasedController {
+        RuleBasedController {
+            min_tire_age_fcy: rule_based_pars.min_tire_age_fcy,
+            min_tire_age_undercut: rule_based_pars.min_tire_age_undercut,
+            min_laps_left: rule_based_pars.min_laps_left,
+            max_gap_undercut: rule_based_pars.max_gap_undercut,
+            plan: strategy.iter().skip(1).cloned().collect(),
+        }
+    }
+
+    /// The method checks if a close rival pitted in the previous lap, i.e. if the car could be
+    /// undercut. The gap before the rival's pit stop is estimated by subtracting the pit stop time
+    /// loss from the current gap.
+    fn check_undercut_threat(&self, strategy_info: &StrategyInfo) -> bool {
+        strategy_info.rivals.iter().any(|rival| {
+            let delta_t = (strategy_info.race_prog - rival.race_prog) * strategy_info.laptime;
+
+            rival.last_inlap.is_some()
+                && rival.last_inlap == strategy_info.cur_lap.checked_sub(1)
+                && (delta_t - strategy_info.t_pit_loss).abs() <= self.max_gap_undercut
+        })
+    }
+
+    /// The method determines the compound with the smallest degradation time loss over the
+    /// remaining laps (including the time loss due to the current track wetness, which is assumed
+    /// to remain constant). If the compound rule of the regulations is not fulfilled yet, only unused
+    /// compounds are considered.
+    fn choose_compound(&self, strategy_info: &StrategyInfo) -> String {
+        let compounds_used = &strategy_info.compounds_used;
+        let mut compounds = strategy_info.driver.get_compounds();
+
+        if !strategy_info
+            .regulations
+            .fulfills_compound_rule(compounds_used)
+            && compounds.iter().any(|x| !compounds_used.contains(x))
+        {
+            compounds.retain(|x| !compounds_used.contains(x))
+        }
+
+        let laps_left = strategy_info.tot_no_laps - strategy_info.cur_lap;
+        let mut compound_best = compounds[0].to_owned();
+        let mut t_loss_best = f64::INFINITY;
+
+        for compound in compounds.iter() {
+            let degr_pars = strategy_info.driver.get_degr_pars(compound);
+            let mut tireset = Tireset::new(compound.to_owned(), 0);
+            let mut t_loss = 0.0;
+
+            for _ in 0..laps_left {
+                t_loss += tireset.t_add_tireset(&degr_pars);
+                tireset.drive_lap();
+            }
+
+            if let Some(weather) = strategy_info.weather.as_ref() {
+                t_loss +=
+                    laps_left as f64 * weather.calc_t_wetness(compound, strategy_info.wetness);
+            }
+
+            if t_loss < t_loss_best {
+                compound_best = compound.to_owned();
+                t_loss_best = t_loss;
+            }
+        }
+
+        compound_best
+    }
+}
+
+impl StrategyController for RuleBasedController {
+    fn decide_pit(&mut self, strategy_info: &StrategyInfo) -> Option<StrategyEntry> {
+        // remove planned pit stops that are outdated
+        self.plan.retain(|x| x.inlap >= strategy_info.cur_lap);
+
+        // pit as planned
+        if !self.plan.is_empty() && self.plan[0].inlap == strategy_info.cur_lap {
+            return Some(self.plan.remove(0));
+        }
+
+        // check for reasons to pit earlier than planned (or additionally)
+        if strategy_info.tot_no_laps - strategy_info.cur_lap < self.min_laps_left {
+            return None;
+        }
+
+        let fcy_active = matches!(strategy_info.flag_state, FlagState::Sc | FlagState::Vsc);
+        let pit_fcy = fcy_active && strategy_info.tire_age >= self.min_tire_age_fcy;
+        let pit_undercut = strategy_info.tire_age >= self.min_tire_age_undercut
+            && !self.plan.is_empty()
+            && self.check_undercut_threat(strategy_info);
+
+        if !pit_fcy && !pit_undercut {
+            return None;
+        }
+
+        // replace the next planned pit stop or add a pit stop
+        let mut strategy_entry = if !self.plan.is_empty() {
+            self.plan.remove(0)
+        } else {
+            StrategyEntry {
+                inlap: 0,
+                tire_start_age: 0,
+                compound: self.choose_compound(strategy_info),
+                refuel_mass: 0.0,
+                driver_initials: String::new(),
+            }
+        };
+
+        strategy_entry.inlap = strategy_info.cur_lap;
+        Some(strategy_entry)
+    }
+}
@@ -0,0 +1,221 @@
+use crate::core::car::Car;
+use crate::core::state_handler::State;
+use anyhow::Context;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// * `path` - Path to the JSON file the telemetry is written to
+/// * `decimation` - Only every decimation-th time step is recorded (1 records every time step)
+#[derive(Debug, Clone)]
+pub struct TelemetryOpts {
+    pub path: PathBuf,
+    pub decimation: u32,
+}
+
+/// CarTelemetry contains the recorded channels of a single car, i.e. one value per recorded time
+/// step.
+///
+/// * `car_no` - Car number
+/// * `s_track` - (m) s coordinate at the end of the time step (negative behind the finish line at
+/// the race start)
+/// * `compl_lap` - Number of completed laps at the end of the time step
+/// * `cur_laptime` - (s) Lap time the car drove with in the time step (infinite during a pit stop
+/// standstill or after the retirement, written as null)
+/// * `state` - State of the statemachine at the end of the time step
+/// * `start_act`, `drs_act`, `duel_act`, `overtaking_act`, `pit_act` - Flags of the statemachine
+/// at the end of the time step
+///
+/// Times and distances are recorded with a precision of three decimals (ms and mm).
+#[derive(Debug, Clone, Serialize)]
+pub struct CarTelemetry {
+    pub car_no: u32,
+    pub s_track: Vec<f64>,
+    pub compl_lap: Vec<u32>,
+    pub cur_laptime: Vec<f64>,
+    pub state: Vec<State>,
+    pub start_act: Vec<bool>,
+    pub drs_act: Vec<bool>,
+    pub duel_act: Vec<bool>,
+    pub overtaking_act: Vec<bool>,
+    pub pit_act: Vec<bool>,
+}
+
+/// Telemetry records the state of every car in every (decimation-th) time step of a race. It is
+/// stored column-wise, i.e. `t_race` contains the race times of the recorded time steps and `cars`
+/// contains one channel per recorded quantity and car.
+#[derive(Debug, Clone, Serialize)]
+pub struct Telemetry {
+    pub decimation: u32,
+    #[serde(skip)]
+    no_timesteps: u32,
+    pub timestep_size: f64,
+    pub t_race: Vec<f64>,
+    pub cars: Vec<CarTelemetry>,
+}
+
+impl Telemetry {
+    pub fn new(cars_list: &[Car], timestep_size: f64, decimation: u32) -> Telemetry {
+        Telemetry {
+            decimation,
+            no_timesteps: 0,
+            timestep_size,
+            t_race: vec![],
+            cars: cars_list
+                .iter()
+                .map(|car| CarTelemetry {
+                    car_no: car.car_no,
+                    s_track: vec![],
+                    compl_lap: vec![],
+                    cur_laptime: vec![],
+                    state: vec![],
+                    start_act: vec![],
+                    drs_act: vec![],
+                    duel_act: vec![],
+                    overtaking_act: vec![],
+                    pit_act: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    /// The method records the state of the cars at the end of the current time step if it is not
+    /// skipped due to the decimation.
+    pub fn record(&mut self, cur_racetime: f64, cars_list: &[Car], cur_laptimes: &[f64]) {
+        self.no_timesteps += 1;
+
+        if !self.no_timesteps.is_multiple_of(self.decimation) {
+            return;
+        }
+
+        self.t_race.push(round_mm(cur_racetime));
+
+        for (i, car) in cars_list.iter().enumerate() {
+            let car_telemetry = &mut self.cars[i];
+
+            car_telemetry.s_track.push(round_mm(car.sh.get_s_track()));
+            car_telemetry.compl_lap.push(car.sh.get_compl_lap());
+            car_telemetry.cur_laptime.push(round_mm(cur_laptimes[i]));
+            car_telemetry.state.push(car.sh.get_state());
+            car_telemetry.start_act.push(car.sh.start_act);
+            car_telemetry.drs_act.push(car.sh.drs_act);
+            car_telemetry.duel_act.push(car.sh.duel_act);
+            car_telemetry.overtaking_act.push(car.sh.overtaking_act);
+            car_telemetry.pit_act.push(car.sh.pit_act);
+        }
+    }
+
+    /// The method writes the recorded telemetry to the JSON file at the inserted path. The file is
+    /// written column-wise, i.e. it contains the keys `timestep_size`, `decimation`, `t_race` (one
+    /// array containing the race times of the recorded time steps), and `cars` (one object per car
+    /// containing the car number and one array per channel, see CarTelemetry).
+    pub fn write_json(&self, filepath: &Path) -> anyhow::Result<()> {
+        // open file
+        let fh = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(filepath)
+            .context(format!(
+                "Failed to open telemetry file {}!",
+                filepath.to_str().unwrap()
+            ))?;
+
+        // write telemetry
+        self.write_columns(BufWriter::new(fh)).context(format!(
+            "Failed to write telemetry to file {}!",
+            filepath.to_str().unwrap()
+        ))?;
+        Ok(())
+    }
+
+    /// write_columns writes the telemetry to the inserted writer (compact JSON).
+    fn write_columns<W: Write>(&self, mut writer: W) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// round_mm rounds the inserted time or distance to three decimals (ms or mm).
+fn round_mm(x: f64) -> f64 {
+    (x * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Telemetry;
+    use crate::core::race::Race;
+    use crate::pre::read_sim_pars::read_example_sim_pars;
+    use approx::assert_ulps_eq;
+
+    #[test]
+    fn test_telemetry_decimation() {
+        let sim_pars = read_example_sim_pars();
+        let race = Race::new(
+            &sim_pars.race_pars,
+            &sim_pars.track_pars,
+            &sim_pars.driver_pars_all,
+            &sim_pars.car_pars_all,
+            0.2,
+            0,
+        );
+        let no_cars = race.cars_list.len();
+        let cur_laptimes = vec![100.0; no_cars];
+        let mut telemetry = Telemetry::new(&race.cars_list, 0.2, 3);
+
+        // only the 3rd and the 6th of 7 time steps are recorded
+        for k in 1..8 {
+            telemetry.record(k as f64 * 0.2, &race.cars_list, &cur_laptimes);
+        }
+
+        assert_eq!(telemetry.t_race.len(), 2);
+        assert_ulps_eq!(telemetry.t_race[0], 0.6);
+        assert_ulps_eq!(telemetry.t_race[1], 1.2);
+        assert_eq!(telemetry.cars.len(), no_cars);
+        assert!(telemetry.cars.iter().all(|x| x.s_track.len() == 2));
+    }
+    #[test]
+    fn test_telemetry_write_columns() {
+        let sim_pars = read_example_sim_pars();
+        let race = Race::new(
+            &sim_pars.race_pars,
+            &sim_pars.track_pars,
+            &sim_pars.driver_pars_all,
+            &sim_pars.car_pars_all,
+            0.2,
+            0,
+        );
+        let mut cur_laptimes = vec![100.0; race.cars_list.len()];
+        cur_laptimes[0] = f64::INFINITY;
+        let mut telemetry = Telemetry::new(&race.cars_list, 0.2, 1);
+        telemetry.record(0.2, &race.cars_list, &cur_laptimes);
+        telemetry.record(0.4, &race.cars_list, &cur_laptimes);
+
+        let mut buffer = vec![];
+        telemetry.write_columns(&mut buffer).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+
+        assert_eq!(json["decimation"], 1);
+        assert_eq!(json["t_race"], serde_json::json!([0.2, 0.4]));
+        assert_eq!(json["cars"][0]["car_no"], race.cars_list[0].car_no);
+        assert_eq!(
+            json["cars"][0]["cur_laptime"],
+            serde_json::json!([null, null])
+        );
+        assert_eq!(
+            json["cars"][1]["cur_laptime"],
+            serde_json::json!([100.0, 100.0])
+        );
+        assert_eq!(
+            json["cars"][1]["state"],
+            serde_json::json!(["Racestart", "Racestart"])
+        );
+        assert_eq!(
+            json["cars"][1]["start_act"],
+            serde_json::json!([true, true])
+        );
+        assert!(json.get("no_timesteps").is_none());
+    }
+}
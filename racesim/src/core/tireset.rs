@@ -1,4 +1,32 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+/// TireCategory distinguishes dry tires (slicks, e.g. A1 to A7) from intermediate (I) and wet (W)
+/// tires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TireCategory {
+    Dry,
+    Intermediate,
+    Wet,
+}
+
+impl TireCategory {
+    pub fn from_compound(compound: &str) -> TireCategory {
+        match compound {
+            "I" => TireCategory::Intermediate,
+            "W" => TireCategory::Wet,
+            _ => TireCategory::Dry,
+        }
+    }
+}
+
+/// * `compound` - Tire compound
+/// * `age` - (laps) Tire age at the race start (0 for new sets, greater than 0 for sets that were
+/// pre-used during practice or qualifying)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TiresetPars {
+    pub compound: String,
+    pub age: u32,
+}
 
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -36,11 +64,16 @@ pub struct DegrPars {
     pub k_2_ln: Option<f64>,
 }
 
+/// * `age_dirty_air` - (laps) Additional tire age due to driving in dirty air
+/// * `overheating` - Accumulated overheating of the tires (sum of the wetness differences of all laps
+/// driven on a track that was too dry for the compound, see Weather)
 #[derive(Debug)]
 pub struct Tireset {
     pub compound: String,
     pub age_tot: u32,
     pub age_cur_stint: u32,
+    pub age_dirty_air: f64,
+    pub overheating: f64,
 }
 
 impl Tireset {
@@ -49,6 +82,8 @@ impl Tireset {
             compound,
             age_tot,
             age_cur_stint: 0,
+            age_dirty_air: 0.0,
+            overheating: 0.0,
         }
     }
 
@@ -75,9 +110,10 @@ impl Tireset {
     /// * `cubic model`: t_tire_degr = k_0 + k_1_cub  * age + k_2_cub  * age**2 + k_3_cub * age**3
     /// * `logarithmic model`: t_tire_degr = k_0 + k_1_ln   * ln(k_2_ln * age + 1)
     ///
-    /// `age` is the total tire age in laps at the start of the current lap.
+    /// `age` is the total tire age in laps at the start of the current lap (including the additional
+    /// age due to driving in dirty air).
     fn calc_tire_degr(&self, degr_pars: &DegrPars) -> f64 {
-        let age_tot = self.age_tot as f64;
+        let age_tot = self.age_tot as f64 + self.age_dirty_air;
 
         match degr_pars.degr_model {
             // linear tire degradation model
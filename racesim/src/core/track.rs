@@ -5,6 +5,14 @@ use serde::Deserialize;
 /// * `t_gap_racepace` - (s) Estimated gap between t_q and best race lap time (due to engine mode
 /// etc.)
 /// * `s_mass` - (s/kg) Lap time mass sensitivity
+/// * `f_lap_var` - Track-specific factor that scales the random lap time variation of the drivers
+/// (e.g. greater than 1.0 on street circuits)
+/// * `p_accident` - Probability of an accident per car and lap
+/// * `t_evo_per_carlap` - (s) Lap time reduction due to track evolution (rubbering-in) per lap
+/// completed by any car (negative)
+/// * `t_evo_max` - (s) Maximum lap time reduction due to track evolution (negative)
+/// * `f_evo_reset_wet` - Fraction of the track evolution that is lost per lap completed by any car
+/// on a fully wet track (scaled by the track wetness, i.e. rain washes the rubber away)
 /// * `t_drseffect` - (s) Lap time reduction when using DRS in all available DRS zones (negative)
 /// * `pit_speedlimit` - (m/s) Speed limit when driving through the pit lane
 /// * `t_loss_firstlap` - (s) Lap time loss due to the start from standstill
@@ -22,12 +30,26 @@ use serde::Deserialize;
 /// * `pits_aft_finishline` - True if pits are located after the finish line, false if located
 /// before
 /// * `overtaking_zones` - (m) Start and end of the overtaking zones
+/// * `overtaking_difficulty` - (s) Track-specific overtaking difficulty, i.e. the advantage of the
+/// attacking car (pace, DRS, top speed) at which an overtaking attempt succeeds with a probability
+/// of about 63% (set null to let the cars overtake freely in the overtaking zones)
+/// * `k_vel_max` - (s/(km/h)) Advantage of the attacking car per km/h of top speed difference
 #[derive(Debug, Deserialize, Clone)]
 pub struct TrackPars {
     pub name: String,
     pub t_q: f64,
     pub t_gap_racepace: f64,
     pub s_mass: f64,
+    #[serde(default)]
+    pub f_lap_var: f64,
+    #[serde(default)]
+    pub p_accident: f64,
+    #[serde(default)]
+    pub t_evo_per_carlap: f64,
+    #[serde(default)]
+    pub t_evo_max: f64,
+    #[serde(default)]
+    pub f_evo_reset_wet: f64,
     pub t_drseffect: f64,
     pub pit_speedlimit: f64,
     pub t_loss_firstlap: f64,
@@ -42,6 +64,10 @@ pub struct TrackPars {
     pub pit_zone: [f64; 2],
     pub pits_aft_finishline: bool,
     pub overtaking_zones: Vec<[f64; 2]>,
+    #[serde(default)]
+    pub overtaking_difficulty: Option<f64>,
+    #[serde(default)]
+    pub k_vel_max: f64,
 }
 
 #[derive(Debug)]
@@ -50,6 +76,11 @@ pub struct Track {
     pub t_q: f64,
     pub t_gap_racepace: f64,
     pub s_mass: f64,
+    pub f_lap_var: f64,
+    pub p_accident: f64,
+    pub t_evo_per_carlap: f64,
+    pub t_evo_max: f64,
+    pub f_evo_reset_wet: f64,
     pub t_drseffect: f64,
     pub pit_speedlimit: f64,
     pub t_loss_firstlap: f64,
@@ -67,6 +98,8 @@ pub struct Track {
     pub pits_aft_finishline: bool,
     pub overtaking_zones: Vec<[f64; 2]>,
     pub overtaking_zones_lap_frac: f64,
+    pub overtaking_difficulty: Option<f64>,
+    pub k_vel_max: f64,
 }
 
 impl Track {
@@ -100,6 +133,11 @@ impl Track {
             t_q: track_pars.t_q,
             t_gap_racepace: track_pars.t_gap_racepace,
             s_mass: track_pars.s_mass,
+            f_lap_var: track_pars.f_lap_var,
+            p_accident: track_pars.p_accident,
+            t_evo_per_carlap: track_pars.t_evo_per_carlap,
+            t_evo_max: track_pars.t_evo_max,
+            f_evo_reset_wet: track_pars.f_evo_reset_wet,
             t_drseffect: track_pars.t_drseffect,
             pit_speedlimit: track_pars.pit_speedlimit,
             t_loss_firstlap: track_pars.t_loss_firstlap,
@@ -117,9 +155,17 @@ impl Track {
             pits_aft_finishline: track_pars.pits_aft_finishline,
             pit_zone: track_pars.pit_zone,
             overtaking_zones: track_pars.overtaking_zones.to_owned(),
+            overtaking_difficulty: track_pars.overtaking_difficulty,
+            k_vel_max: track_pars.k_vel_max,
         }
     }
 
+    /// The method returns the lap time reduction due to track evolution after the inserted
+    /// (effective) number of laps completed by all cars (saturating at t_evo_max).
+    pub fn get_t_evo(&self, evo_carlaps: f64) -> f64 {
+        (self.t_evo_per_carlap * evo_carlaps).max(self.t_evo_max)
+    }
+
     /// The method returns the approximate time loss when driving through the pit lane.
     pub fn get_pit_drive_timeloss(&self) -> f64 {
         let pit_zone_lap_frac = self.track_length_pit_zone / self.length;
@@ -0,0 +1,149 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// * `wetness_range` - Range (min, max) of the track wetness in which the compound works without
+/// any time loss (0.0 dry, 1.0 fully wet)
+/// * `k_wetness` - (s) Lap time loss per unit of track wetness outside the wetness range (e.g. slicks
+/// on a wet track, wet tires on a dry track)
+/// * `k_overheating` - (s/lap) Additional tire degradation per lap driven below the wetness range
+/// (scaled by the wetness difference), i.e. overheating of wet tires on a drying track
+#[derive(Debug, Deserialize, Clone)]
+pub struct WetnessPars {
+    pub wetness_range: [f64; 2],
+    pub k_wetness: f64,
+    pub k_overheating: f64,
+}
+
+/// * `wetness` - List of (race time (s), track wetness) points that describe the track wetness over
+/// the race time (linearly interpolated, constant before the first and after the last point)
+/// * `compound_pars` - Wetness parameters of every tire compound
+#[derive(Debug, Deserialize, Clone)]
+pub struct WeatherPars {
+    pub wetness: Vec<[f64; 2]>,
+    pub compound_pars: HashMap<String, WetnessPars>,
+}
+
+#[derive(Debug)]
+pub struct Weather {
+    wetness: Vec<[f64; 2]>,
+    compound_pars: HashMap<String, WetnessPars>,
+}
+
+impl Weather {
+    pub fn new(weather_pars: &WeatherPars) -> Weather {
+        // sort wetness points by their race times such that they can be interpolated
+        let mut wetness = weather_pars.wetness.to_owned();
+        wetness.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap());
+
+        Weather {
+            wetness,
+            compound_pars: weather_pars.compound_pars.to_owned(),
+        }
+    }
+
+    /// The method returns the track wetness at the inserted race time.
+    pub fn get_wetness(&self, t_race: f64) -> f64 {
+        let idx = self.wetness.iter().position(|x| x[0] > t_race);
+
+        match idx {
+            None => self.wetness.last().map_or(0.0, |x| x[1]),
+            Some(0) => self.wetness[0][1],
+            Some(idx) => {
+                let [t_0, wetness_0] = self.wetness[idx - 1];
+                let [t_1, wetness_1] = self.wetness[idx];
+                wetness_0 + (wetness_1 - wetness_0) * (t_race - t_0) / (t_1 - t_0)
+            }
+        }
+    }
+
+    /// The method returns the lap time loss of the inserted compound at the inserted track wetness.
+    pub fn calc_t_wetness(&self, compound: &str, wetness: f64) -> f64 {
+        let wetness_pars = self.get_wetness_pars(compound);
+        wetness_pars.k_wetness * calc_wetness_outside_range(wetness_pars.wetness_range, wetness)
+    }
+
+    /// The method returns the lap time loss due to the inserted accumulated tire overheating (see
+    /// calc_overheating).
+    pub fn calc_t_overheating(&self, compound: &str, overheating: f64) -> f64 {
+        self.get_wetness_pars(compound).k_overheating * overheating
+    }
+
+    /// The method returns the tire overheating of the inserted compound caused by a lap driven at
+    /// the inserted track wetness, i.e. the wetness difference if the track is drier than the
+    /// wetness range of the compound.
+    pub fn calc_overheating(&self, compound: &str, wetness: f64) -> f64 {
+        let wetness_range = self.get_wetness_pars(compound).wetness_range;
+
+        if wetness < wetness_range[0] {
+            wetness_range[0] - wetness
+        } else {
+            0.0
+        }
+    }
+
+    fn get_wetness_pars(&self, compound: &str) -> &WetnessPars {
+        self.compound_pars
+            .get(compound)
+            .expect("Missing wetness parameters for the requested compound!")
+    }
+}
+
+/// calc_wetness_outside_range returns the difference between the inserted track wetness and the
+/// inserted wetness range (zero if the wetness is within the range).
+fn calc_wetness_outside_range(wetness_range: [f64; 2], wetness: f64) -> f64 {
+    if wetness < wetness_range[0] {
+        wetness_range[0] - wetness
+    } else if wetness > wetness_range[1] {
+        wetness - wetness_range[1]
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Weather, WeatherPars};
+    use approx::assert_ulps_eq;
+    use std::collections::HashMap;
+
+    fn create_weather(wetness: Vec<[f64; 2]>) -> Weather {
+        Weather::new(&WeatherPars {
+            wetness,
+            compound_pars: HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn test_wetness_before_first_point() {
+        let weather = create_weather(vec![[100.0, 0.2], [200.0, 0.8]]);
+        assert_ulps_eq!(weather.get_wetness(0.0), 0.2);
+    }
+    #[test]
+    fn test_wetness_at_points() {
+        let weather = create_weather(vec![[100.0, 0.2], [200.0, 0.8], [300.0, 0.4]]);
+        assert_ulps_eq!(weather.get_wetness(100.0), 0.2);
+        assert_ulps_eq!(weather.get_wetness(200.0), 0.8);
+        assert_ulps_eq!(weather.get_wetness(300.0), 0.4);
+    }
+    #[test]
+    fn test_wetness_between_points() {
+        let weather = create_weather(vec![[100.0, 0.2], [200.0, 0.8], [300.0, 0.4]]);
+        assert_ulps_eq!(weather.get_wetness(150.0), 0.5);
+        assert_ulps_eq!(weather.get_wetness(275.0), 0.5);
+    }
+    #[test]
+    fn test_wetness_after_last_point() {
+        let weather = create_weather(vec![[100.0, 0.2], [200.0, 0.8]]);
+        assert_ulps_eq!(weather.get_wetness(1000.0), 0.8);
+    }
+    #[test]
+    fn test_wetness_unsorted_points() {
+        let weather = create_weather(vec![[200.0, 0.8], [100.0, 0.2]]);
+        assert_ulps_eq!(weather.get_wetness(150.0), 0.5);
+    }
+    #[test]
+    fn test_wetness_no_points() {
+        let weather = create_weather(vec![]);
+        assert_ulps_eq!(weather.get_wetness(100.0), 0.0);
+    }
+}
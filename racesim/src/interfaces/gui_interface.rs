@@ -21,4 +21,5 @@ pub struct CarState {
 pub struct RaceState {
     pub car_states: Vec<CarState>,
     pub flag_state: FlagState,
+    pub sc_race_prog: Option<f64>,
 }
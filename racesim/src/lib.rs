@@ -1,4 +1,5 @@
 pub mod core;
 pub mod interfaces;
+pub mod opt;
 pub mod post;
 pub mod pre;
@@ -0,0 +1,479 @@
+use crate::core::car::{Car, StrategyEntry};
+use crate::core::driver::Driver;
+use crate::core::handle_race::handle_race;
+use crate::core::regulations::Regulations;
+use crate::core::track::Track;
+use crate::pre::read_sim_pars::SimPars;
+use anyhow::Context;
+use helpers::general::{percentile, InputValueError};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/// StrategyCandidate contains a strategy that is evaluated by the optimizer together with its
+/// estimated race time.
+///
+/// * `strategy` - Strategy entries (first entry: start configuration)
+/// * `t_race_est` - (s) Race time estimated on a free track (no traffic, no random influences)
+#[derive(Debug, Clone)]
+pub struct StrategyCandidate {
+    pub strategy: Vec<StrategyEntry>,
+    pub t_race_est: f64,
+}
+
+impl StrategyCandidate {
+    /// get_no_stops returns the number of pit stops of the strategy.
+    pub fn get_no_stops(&self) -> usize {
+        self.strategy.len() - 1
+    }
+
+    /// get_description returns a short description of the strategy, e.g. A5 -> 20 -> A4.
+    pub fn get_description(&self) -> String {
+        let mut description = self.strategy[0].compound.to_owned();
+
+        for strategy_entry in self.strategy.iter().skip(1) {
+            write!(
+                &mut description,
+                " -> {} -> {}",
+                strategy_entry.inlap, strategy_entry.compound
+            )
+            .unwrap();
+        }
+
+        description
+    }
+}
+
+/// StrategyEvaluation contains the result of the full race simulations of a strategy candidate.
+///
+/// * `candidate` - Evaluated strategy candidate
+/// * `position_mean` - Mean final position of the car
+/// * `racetime_mean` - (s) Mean race time of the car (only races the car finished are considered,
+/// NaN if it never finished)
+/// * `racetime_p50` - (s) Median of the race time of the car
+/// * `p_dnf` - Share of the races in which the car retired
+#[derive(Debug, Clone)]
+pub struct StrategyEvaluation {
+    pub candidate: StrategyCandidate,
+    pub position_mean: f64,
+    pub racetime_mean: f64,
+    pub racetime_p50: f64,
+    pub p_dnf: f64,
+}
+
+/// StrategyOptimizer determines the best pit stop strategy for a single car. At first, all 1- to
+/// max_no_stops-stop strategies over the compounds available for the start driver are enumerated
+/// (respecting the two-compound rule and a minimum stint length). They are pre-ranked by a race
+/// time estimation on a free track, which is fast. The best candidates are then evaluated with
+/// full race simulations, such that traffic and overtaking are considered.
+#[derive(Debug)]
+pub struct StrategyOptimizer {
+    sim_pars: SimPars,
+    car_no: u32,
+    max_no_stops: u32,
+    min_stint_length: u32,
+    no_candidates: u32,
+}
+
+impl StrategyOptimizer {
+    pub fn new(
+        sim_pars: &SimPars,
+        car_no: u32,
+        max_no_stops: u32,
+        min_stint_length: u32,
+        no_candidates: u32,
+    ) -> anyhow::Result<StrategyOptimizer> {
+        if !sim_pars.race_pars.participants.contains(&car_no) {
+            return Err(InputValueError)
+                .context(format!("Car {} is not a participant of the race!", car_no));
+        }
+
+        if !(1..=3).contains(&max_no_stops) {
+            return Err(InputValueError)
+                .context("max_no_stops is not within the required range [1, 3]!");
+        }
+
+        if min_stint_length < 1 || no_candidates < 1 {
+            return Err(InputValueError)
+                .context("min_stint_length and no_candidates must be at least equal to one!");
+        }
+
+        Ok(StrategyOptimizer {
+            sim_pars: sim_pars.to_owned(),
+            car_no,
+            max_no_stops,
+            min_stint_length,
+            no_candidates,
+        })
+    }
+
+    /// The method enumerates all valid strategies and returns the candidates for the full race
+    /// simulations: for every compound sequence the in-laps with the best estimated race time are
+    /// determined, of which the best no_candidates sequences per number of stops are kept.
+    pub fn get_candidates(&self) -> Vec<StrategyCandidate> {
+        let car_pars = &self.sim_pars.car_pars_all[&self.car_no];
+        let start_driver = &self.sim_pars.driver_pars_all[&car_pars.strategy[0].driver_initials];
+
+        // get available compounds (sorted to obtain a reproducible order)
+        let mut compounds: Vec<String> = start_driver.degr_pars_all.keys().cloned().collect();
+        compounds.sort();
+
+        // create track and drivers required for the race time estimation
+        let track = Track::new(&self.sim_pars.track_pars);
+        let drivers_list: HashMap<String, Rc<Driver>> = self
+            .sim_pars
+            .driver_pars_all
+            .iter()
+            .map(|(initials, driver_pars)| (initials.to_owned(), Rc::new(Driver::new(driver_pars))))
+            .collect();
+
+        let regulations = Regulations::new(self.sim_pars.race_pars.season);
+        let mut candidates_all = vec![];
+
+        for no_stops in 1..self.max_no_stops as usize + 1 {
+            let mut candidates: Vec<StrategyCandidate> = vec![];
+
+            for compound_seq in get_compound_seqs(&compounds, no_stops + 1).iter() {
+                if !regulations.fulfills_compound_rule(compound_seq) {
+                    continue;
+                }
+
+                // determine the best in-laps for the current compound sequence
+                let mut candidate_best: Option<StrategyCandidate> = None;
+
+                for inlaps in get_inlap_seqs(
+                    self.sim_pars.race_pars.tot_no_laps,
+                    no_stops,
+                    self.min_stint_length,
+                )
+                .iter()
+                {
+                    let strategy = self.create_strategy(compound_seq, inlaps);
+                    let t_race_est = self.estimate_racetime(&strategy, &track, &drivers_list);
+
+                    let is_better = match candidate_best.as_ref() {
+                        Some(x) => t_race_est < x.t_race_est,
+                        None => true,
+                    };
+
+                    if is_better {
+                        candidate_best = Some(StrategyCandidate {
+                            strategy,
+                            t_race_est,
+                        })
+                    }
+                }
+
+                if let Some(candidate) = candidate_best {
+                    candidates.push(candidate)
+                }
+            }
+
+            // keep the best compound sequences of the current number of stops
+            candidates.sort_by(|a, b| a.t_race_est.partial_cmp(&b.t_race_est).unwrap());
+            candidates.truncate(self.no_candidates as usize);
+            candidates_all.append(&mut candidates);
+        }
+
+        candidates_all
+    }
+
+    /// The method evaluates the inserted strategy candidate by full race simulations, one per
+    /// inserted seed. The same seeds should be used for all candidates such that they are compared
+    /// under the same random influences.
+    pub fn evaluate(
+        &self,
+        candidate: &StrategyCandidate,
+        timestep_size: f64,
+        seeds: &[u64],
+    ) -> anyhow::Result<StrategyEvaluation> {
+        // set strategy of the car
+        let mut sim_pars = self.sim_pars.to_owned();
+        sim_pars
+            .car_pars_all
+            .get_mut(&self.car_no)
+            .unwrap()
+            .strategy = candidate.strategy.to_owned();
+
+        // simulate races and determine final position and race time of the car
+        let mut positions = Vec::with_capacity(seeds.len());
+        let mut racetimes = Vec::with_capacity(seeds.len());
+        let mut no_dnfs = 0;
+
+        for &seed in seeds.iter() {
+            let race_result = handle_race(&sim_pars, timestep_size, seed, None, 1.0, None)?;
+            let idx = race_result
+                .car_driver_pairs
+                .iter()
+                .position(|x| x.car_no == self.car_no)
+                .unwrap();
+
+            positions.push(race_result.get_final_positions()[idx] as f64);
+
+            if race_result.get_dnf(idx) {
+                no_dnfs += 1;
+            } else {
+                racetimes.push(race_result.get_final_racetime(idx));
+            }
+        }
+
+        let (racetime_mean, racetime_p50) = if !racetimes.is_empty() {
+            (
+                racetimes.iter().sum::<f64>() / racetimes.len() as f64,
+                percentile(&racetimes, 50.0),
+            )
+        } else {
+            (f64::NAN, f64::NAN)
+        };
+
+        Ok(StrategyEvaluation {
+            candidate: candidate.to_owned(),
+            position_mean: positions.iter().sum::<f64>() / seeds.len() as f64,
+            racetime_mean,
+            racetime_p50,
+            p_dnf: no_dnfs as f64 / seeds.len() as f64,
+        })
+    }
+
+    /// The method creates the strategy entries for the inserted compound and in-lap sequences.
+    /// The start configuration (tire age, start driver) is taken from the car parameters.
+    fn create_strategy(&self, compound_seq: &[String], inlaps: &[u32]) -> Vec<StrategyEntry> {
+        let start_entry = &self.sim_pars.car_pars_all[&self.car_no].strategy[0];
+
+        let mut strategy = vec![StrategyEntry {
+            inlap: 0,
+            tire_start_age: start_entry.tire_start_age,
+            compound: compound_seq[0].to_owned(),
+            refuel_mass: 0.0,
+            driver_initials: start_entry.driver_initials.to_owned(),
+        }];
+
+        for (compound, &inlap) in compound_seq.iter().skip(1).zip(inlaps.iter()) {
+            strategy.push(StrategyEntry {
+                inlap,
+                tire_start_age: 0,
+                compound: compound.to_owned(),
+                refuel_mass: 0.0,
+                driver_initials: String::new(),
+            })
+        }
+
+        strategy
+    }
+
+    /// The method estimates the race time of the car for the inserted strategy on a free track,
+    /// i.e. without interactions with other cars and without random influences.
+    fn estimate_racetime(
+        &self,
+        strategy: &[StrategyEntry],
+        track: &Track,
+        drivers_list: &HashMap<String, Rc<Driver>>,
+    ) -> f64 {
+        // create car
+        let mut car_pars = self.sim_pars.car_pars_all[&self.car_no].to_owned();
+        car_pars.strategy = strategy.to_owned();
+
+        let mut car = Car::new(
+            &car_pars,
+            Rc::clone(&drivers_list[&car_pars.strategy[0].driver_initials]),
+        );
+
+        // sum up lap times and pit stop time losses
+        let mut t_race_est = track.t_loss_firstlap;
+
+        for lap in 1..self.sim_pars.race_pars.tot_no_laps + 1 {
+            t_race_est += track.t_q + track.t_gap_racepace + car.calc_basic_timeloss(track.s_mass);
+            car.drive_lap();
+
+            if car.pit_this_lap(lap) {
+                t_race_est += track.get_pit_drive_timeloss()
+                    + car.t_add_pit_standstill(
+                        lap,
+                        car.get_t_pit_tirechange(),
+                        &self.sim_pars.race_pars.penalty_pars,
+                    );
+                car.perform_pitstop(lap, drivers_list);
+            }
+        }
+
+        t_race_est
+    }
+}
+
+/// rank_strategies sorts the inserted strategy evaluations by the mean final position and
+/// afterward by the mean race time of the car (strategies without a finished race, i.e. with a
+/// NaN mean race time, are ranked last).
+pub fn rank_strategies(evaluations: &mut [StrategyEvaluation]) {
+    evaluations.sort_by(|a, b| {
+        a.position_mean.partial_cmp(&b.position_mean).unwrap().then(
+            match (a.racetime_mean.is_nan(), b.racetime_mean.is_nan()) {
+                (false, false) => a.racetime_mean.partial_cmp(&b.racetime_mean).unwrap(),
+                (a_nan, b_nan) => a_nan.cmp(&b_nan),
+            },
+        )
+    });
+}
+
+/// print_strategy_ranking prints the ranked strategy evaluations to the console output.
+pub fn print_strategy_ranking(evaluations: &[StrategyEvaluation], car_no: u32, no_sim_runs: u32) {
+    let mut tmp_string = format!(
+        "{:>4}, {:>5}, {:40}, {:>8}, {:>10}, {:>10}, {:>10}, {:>6}\n",
+        "rank", "stops", "strategy", "pos mean", "t mean", "t p50", "t est", "p dnf"
+    );
+
+    for (i, evaluation) in evaluations.iter().enumerate() {
+        writeln!(
+            &mut tmp_string,
+            "{:4}, {:5}, {:40}, {:8.2}, {:9.3}s, {:9.3}s, {:9.3}s, {:5.1}%",
+            i + 1,
+            evaluation.candidate.get_no_stops(),
+            evaluation.candidate.get_description(),
+            evaluation.position_mean,
+            evaluation.racetime_mean,
+            evaluation.racetime_p50,
+            evaluation.candidate.t_race_est,
+            evaluation.p_dnf * 100.0
+        )
+        .unwrap();
+    }
+
+    println!(
+        "RESULT: Strategy ranking of car {} ({} runs per strategy)",
+        car_no, no_sim_runs
+    );
+    println!("{}", tmp_string);
+}
+
+/// get_compound_seqs returns all sequences of the inserted length that can be built from the
+/// inserted compounds (repetitions allowed).
+fn get_compound_seqs(compounds: &[String], seq_length: usize) -> Vec<Vec<String>> {
+    let mut seqs: Vec<Vec<String>> = vec![vec![]];
+
+    for _ in 0..seq_length {
+        seqs = seqs
+            .iter()
+            .flat_map(|seq| {
+                compounds.iter().map(move |compound| {
+                    let mut seq_new = seq.to_owned();
+                    seq_new.push(compound.to_owned());
+                    seq_new
+                })
+            })
+            .collect();
+    }
+
+    seqs
+}
+
+/// get_inlap_seqs returns all sequences of in-laps for the inserted number of stops such that
+/// every stint has at least the minimum stint length.
+fn get_inlap_seqs(tot_no_laps: u32, no_stops: usize, min_stint_length: u32) -> Vec<Vec<u32>> {
+    let mut seqs: Vec<Vec<u32>> = vec![vec![]];
+
+    for stop in 0..no_stops {
+        // remaining stops after the current one must still fit into the race
+        let no_stops_left = (no_stops - stop - 1) as u32;
+
+        seqs = seqs
+            .iter()
+            .flat_map(|seq| {
+                let inlap_min = seq.last().unwrap_or(&0) + min_stint_length;
+                let inlap_max = tot_no_laps.saturating_sub((no_stops_left + 1) * min_stint_length);
+
+                (inlap_min..inlap_max + 1).map(move |inlap| {
+                    let mut seq_new = seq.to_owned();
+                    seq_new.push(inlap);
+                    seq_new
+                })
+            })
+            .collect();
+    }
+
+    seqs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrategyOptimizer;
+    use super::{get_inlap_seqs, rank_strategies, StrategyCandidate, StrategyEvaluation};
+    use crate::core::regulations::Regulations;
+    use crate::pre::read_sim_pars::read_example_sim_pars;
+
+    fn create_evaluation(inlap: u32, position_mean: f64, racetime_mean: f64) -> StrategyEvaluation {
+        let sim_pars = read_example_sim_pars();
+        let mut strategy = sim_pars.car_pars_all[&44].strategy.to_owned();
+        strategy[1].inlap = inlap;
+
+        StrategyEvaluation {
+            candidate: StrategyCandidate {
+                strategy,
+                t_race_est: 0.0,
+            },
+            position_mean,
+            racetime_mean,
+            racetime_p50: racetime_mean,
+            p_dnf: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_get_inlap_seqs() {
+        assert_eq!(
+            get_inlap_seqs(10, 2, 3),
+            vec![vec![3, 6], vec![3, 7], vec![4, 7]]
+        );
+    }
+    #[test]
+    fn test_get_candidates() {
+        let sim_pars = read_example_sim_pars();
+        let tot_no_laps = sim_pars.race_pars.tot_no_laps;
+        let optimizer = StrategyOptimizer::new(&sim_pars, 44, 2, 8, 2).unwrap();
+        let regulations = Regulations::new(sim_pars.race_pars.season);
+        let candidates = optimizer.get_candidates();
+
+        for no_stops in 1..3 {
+            let candidates_stops: Vec<&StrategyCandidate> = candidates
+                .iter()
+                .filter(|x| x.get_no_stops() == no_stops)
+                .collect();
+            assert!(!candidates_stops.is_empty() && candidates_stops.len() <= 2);
+            assert!(candidates_stops
+                .windows(2)
+                .all(|x| x[0].t_race_est <= x[1].t_race_est));
+        }
+
+        for candidate in candidates.iter() {
+            // maximum number of stops
+            assert!((1..3).contains(&candidate.get_no_stops()));
+
+            // compound rule
+            let compounds: Vec<String> = candidate
+                .strategy
+                .iter()
+                .map(|x| x.compound.to_owned())
+                .collect();
+            assert!(regulations.fulfills_compound_rule(&compounds));
+
+            // minimum stint length
+            let mut inlaps: Vec<u32> = candidate.strategy.iter().map(|x| x.inlap).collect();
+            inlaps.push(tot_no_laps);
+            assert!(inlaps.windows(2).all(|x| x[1] >= x[0] + 8));
+        }
+    }
+    #[test]
+    fn test_rank_strategies() {
+        let mut evaluations = vec![
+            create_evaluation(20, 2.0, 5900.0),
+            create_evaluation(25, 2.0, f64::NAN),
+            create_evaluation(30, 2.0, 5800.0),
+            create_evaluation(35, 1.0, 5950.0),
+        ];
+        rank_strategies(&mut evaluations);
+
+        let inlaps: Vec<u32> = evaluations
+            .iter()
+            .map(|x| x.candidate.strategy[1].inlap)
+            .collect();
+        assert_eq!(inlaps, vec![35, 30, 20, 25]);
+    }
+}
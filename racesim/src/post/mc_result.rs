@@ -0,0 +1,407 @@
+use crate::core::race::FcyPhaseType;
+use crate::post::race_result::{ClassificationStatus, RaceResult};
+use anyhow::Context;
+use helpers::general::percentile;
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+// number of positions that are rewarded with championship points
+const NO_POINTS_POSITIONS: usize = 10;
+
+/// CarStats contains the statistics of a single car aggregated over all simulation runs.
+///
+/// * `car_no` - Car number
+/// * `driver_initials` - Initials of the start driver
+/// * `position_probs` - Probability for each finishing position (first entry: P1)
+/// * `position_mean` - Mean finishing position
+/// * `lap_1_position_probs` - Probability for each position after the first lap (first entry: P1,
+/// the probabilities do not sum up to 1.0 if the car did not complete the first lap in every race)
+/// * `lap_1_position_mean` - Mean position after the first lap (only races in which the car
+/// completed the first lap are considered, NaN if it never completed it)
+/// * `p_win` - Probability to win the race
+/// * `p_podium` - Probability to finish on the podium
+/// * `p_points` - Probability to finish within the points (like p_win and p_podium, only races in
+/// which the car is classified are counted, i.e. a position of a not classified car never scores)
+/// * `p_dnf` - Probability to retire from the race
+/// * `racetime_mean` - (s) Mean race time (only races the car finished are considered, NaN if it
+/// never finished)
+/// * `racetime_p05` - (s) 5th percentile of the race time
+/// * `racetime_p50` - (s) Median of the race time
+/// * `racetime_p95` - (s) 95th percentile of the race time
+/// * `laptimes_mean` - (s) Mean lap time for each lap (same indexing as in RaceResult, i.e. the
+/// first entry is zero)
+#[derive(Debug, Serialize)]
+pub struct CarStats {
+    pub car_no: u32,
+    pub driver_initials: String,
+    pub position_probs: Vec<f64>,
+    pub position_mean: f64,
+    pub lap_1_position_probs: Vec<f64>,
+    pub lap_1_position_mean: f64,
+    pub p_win: f64,
+    pub p_podium: f64,
+    pub p_points: f64,
+    pub p_dnf: f64,
+    pub racetime_mean: f64,
+    pub racetime_p05: f64,
+    pub racetime_p50: f64,
+    pub racetime_p95: f64,
+    pub laptimes_mean: Vec<f64>,
+}
+
+/// McResult contains the results of multiple simulation runs (Monte Carlo simulation) aggregated
+/// to per-car statistics.
+///
+/// * `no_sim_runs` - Number of aggregated simulation runs
+/// * `tot_no_laps` - Total number of laps in the race
+/// * `p_sc` - Share of the simulation runs that contain a safety car phase
+/// * `p_vsc` - Share of the simulation runs that contain a virtual safety car phase
+/// * `car_stats` - Statistics of the cars (sorted by mean finishing position)
+#[derive(Debug, Serialize)]
+pub struct McResult {
+    pub no_sim_runs: u32,
+    pub tot_no_laps: u32,
+    pub p_sc: f64,
+    pub p_vsc: f64,
+    pub car_stats: Vec<CarStats>,
+}
+
+impl McResult {
+    /// The method aggregates the inserted race results. All race results must belong to the same
+    /// race, i.e. contain the same participants in the same order. Since any iterator over race
+    /// results can be inserted, the results can be filtered beforehand, e.g. to aggregate only
+    /// races with a safety car phase. An empty input results in empty statistics.
+    pub fn new<'a, I>(race_results: I) -> McResult
+    where
+        I: IntoIterator<Item = &'a RaceResult>,
+    {
+        let race_results: Vec<&RaceResult> = race_results.into_iter().collect();
+
+        if race_results.is_empty() {
+            return McResult {
+                no_sim_runs: 0,
+                tot_no_laps: 0,
+                p_sc: 0.0,
+                p_vsc: 0.0,
+                car_stats: vec![],
+            };
+        }
+
+        let no_sim_runs = race_results.len();
+        let no_cars = race_results[0].car_driver_pairs.len();
+        let tot_no_laps = race_results[0].tot_no_laps;
+
+        // determine final positions of all cars in all races and whether they were classified
+        let mut positions_all: Vec<Vec<u32>> = Vec::with_capacity(no_sim_runs);
+        let mut classified_all: Vec<Vec<bool>> = Vec::with_capacity(no_sim_runs);
+
+        for race_result in race_results.iter() {
+            let mut positions = vec![0; no_cars];
+            let mut classified = vec![false; no_cars];
+
+            for entry in race_result.get_final_classification().iter() {
+                positions[entry.car_idx] = entry.position;
+                classified[entry.car_idx] = entry.status != ClassificationStatus::NotClassified;
+            }
+
+            positions_all.push(positions);
+            classified_all.push(classified);
+        }
+
+        // determine positions of all cars after the first lap in all races (zero if the car did
+        // not complete the first lap)
+        let lap_1_positions_all: Vec<Vec<u32>> = race_results
+            .iter()
+            .map(|race_result| {
+                let mut positions = vec![0; no_cars];
+
+                for entry in race_result.get_lap_classification(1).iter() {
+                    positions[entry.car_idx] = entry.position;
+                }
+
+                positions
+            })
+            .collect();
+
+        // aggregate statistics for every car
+        let mut car_stats = Vec::with_capacity(no_cars);
+
+        for i in 0..no_cars {
+            // position distribution
+            let mut position_probs = vec![0.0; no_cars];
+
+            for positions in positions_all.iter() {
+                position_probs[positions[i] as usize - 1] += 1.0 / no_sim_runs as f64;
+            }
+
+            let position_mean = positions_all
+                .iter()
+                .map(|positions| positions[i] as f64)
+                .sum::<f64>()
+                / no_sim_runs as f64;
+
+            // position distribution after the first lap
+            let mut lap_1_position_probs = vec![0.0; no_cars];
+
+            for positions in lap_1_positions_all.iter().filter(|x| x[i] > 0) {
+                lap_1_position_probs[positions[i] as usize - 1] += 1.0 / no_sim_runs as f64;
+            }
+
+            let lap_1_positions: Vec<f64> = lap_1_positions_all
+                .iter()
+                .filter(|positions| positions[i] > 0)
+                .map(|positions| positions[i] as f64)
+                .collect();
+
+            let lap_1_position_mean = if !lap_1_positions.is_empty() {
+                lap_1_positions.iter().sum::<f64>() / lap_1_positions.len() as f64
+            } else {
+                f64::NAN
+            };
+
+            // probability to finish within the inserted position (classified cars only)
+            let p_position_max = |position_max: usize| {
+                positions_all
+                    .iter()
+                    .zip(classified_all.iter())
+                    .filter(|(positions, classified)| {
+                        classified[i] && positions[i] as usize <= position_max
+                    })
+                    .count() as f64
+                    / no_sim_runs as f64
+            };
+
+            // retirements
+            let no_dnfs = race_results
+                .iter()
+                .filter(|race_result| race_result.get_dnf(i))
+                .count();
+
+            // race times (races in which the car retired are not considered)
+            let racetimes: Vec<f64> = race_results
+                .iter()
+                .filter(|race_result| !race_result.get_dnf(i))
+                .map(|race_result| race_result.get_final_racetime(i))
+                .collect();
+
+            let (racetime_mean, racetime_p05, racetime_p50, racetime_p95) = if !racetimes.is_empty()
+            {
+                (
+                    racetimes.iter().sum::<f64>() / racetimes.len() as f64,
+                    percentile(&racetimes, 5.0),
+                    percentile(&racetimes, 50.0),
+                    percentile(&racetimes, 95.0),
+                )
+            } else {
+                (f64::NAN, f64::NAN, f64::NAN, f64::NAN)
+            };
+
+            // mean lap times (laps that were not driven, e.g. because the car was lapped, are not
+            // considered)
+            let mut laptimes_mean = vec![0.0; tot_no_laps as usize + 1];
+
+            for (lap, laptime_mean) in laptimes_mean.iter_mut().enumerate().skip(1) {
+                let laptimes: Vec<f64> = race_results
+                    .iter()
+                    .map(|race_result| race_result.laptimes[i][lap])
+                    .filter(|&laptime| laptime > 0.0)
+                    .collect();
+
+                if !laptimes.is_empty() {
+                    *laptime_mean = laptimes.iter().sum::<f64>() / laptimes.len() as f64
+                }
+            }
+
+            car_stats.push(CarStats {
+                car_no: race_results[0].car_driver_pairs[i].car_no,
+                driver_initials: race_results[0].car_driver_pairs[i]
+                    .driver_initials
+                    .to_owned(),
+                p_win: p_position_max(1),
+                p_podium: p_position_max(3),
+                p_points: p_position_max(NO_POINTS_POSITIONS),
+                position_probs,
+                position_mean,
+                lap_1_position_probs,
+                lap_1_position_mean,
+                p_dnf: no_dnfs as f64 / no_sim_runs as f64,
+                racetime_mean,
+                racetime_p05,
+                racetime_p50,
+                racetime_p95,
+                laptimes_mean,
+            })
+        }
+
+        // sort car statistics by mean position
+        car_stats.sort_by(|a, b| a.position_mean.partial_cmp(&b.position_mean).unwrap());
+
+        // determine share of races with FCY phases
+        let p_sc = race_results
+            .iter()
+            .filter(|race_result| race_result.get_fcy_phase_occurred(&FcyPhaseType::Sc))
+            .count() as f64
+            / no_sim_runs as f64;
+        let p_vsc = race_results
+            .iter()
+            .filter(|race_result| race_result.get_fcy_phase_occurred(&FcyPhaseType::Vsc))
+            .count() as f64
+            / no_sim_runs as f64;
+
+        McResult {
+            no_sim_runs: no_sim_runs as u32,
+            tot_no_laps,
+            p_sc,
+            p_vsc,
+            car_stats,
+        }
+    }
+
+    /// print_summary prints a summary table of the aggregated results as well as the finishing
+    /// position distribution and the position distribution after the first lap to the console
+    /// output. The inserted title is used as a headline.
+    pub fn print_summary(&self, title: &str) {
+        // create string for the summary table
+        let mut tmp_string_summary = format!(
+            "{:12},{:>9},{:>7},{:>9},{:>9},{:>7},{:>10},{:>10},{:>10},{:>10}\n",
+            "car (driver)",
+            "pos mean",
+            "p win",
+            "p podium",
+            "p points",
+            "p dnf",
+            "t mean",
+            "t p05",
+            "t p50",
+            "t p95"
+        );
+
+        for car_stats in self.car_stats.iter() {
+            writeln!(
+                &mut tmp_string_summary,
+                "{:3} ({})   ,{:>9.2},{:>6.1}%,{:>8.1}%,{:>8.1}%,{:>6.1}%,{:>9.3}s,{:>9.3}s,{:>9.3}s,{:>9.3}s",
+                car_stats.car_no,
+                car_stats.driver_initials,
+                car_stats.position_mean,
+                car_stats.p_win * 100.0,
+                car_stats.p_podium * 100.0,
+                car_stats.p_points * 100.0,
+                car_stats.p_dnf * 100.0,
+                car_stats.racetime_mean,
+                car_stats.racetime_p05,
+                car_stats.racetime_p50,
+                car_stats.racetime_p95,
+            )
+            .unwrap();
+        }
+
+        // create strings for the position distributions
+        let tmp_string_positions = self.get_position_distribution_string(|x| &x.position_probs);
+        let tmp_string_lap_1_positions =
+            self.get_position_distribution_string(|x| &x.lap_1_position_probs);
+
+        // print everything to the console
+        println!(
+            "RESULT: {} ({} runs, SC in {:.1}%, VSC in {:.1}%)",
+            title,
+            self.no_sim_runs,
+            self.p_sc * 100.0,
+            self.p_vsc * 100.0
+        );
+        println!("{}", tmp_string_summary);
+
+        println!("RESULT: Finishing position distribution");
+        println!("{}", tmp_string_positions);
+
+        println!("RESULT: Position distribution after lap 1");
+        println!("{}", tmp_string_lap_1_positions);
+    }
+
+    /// get_position_distribution_string returns a table of the position distribution that is
+    /// selected from the car statistics by the inserted function.
+    fn get_position_distribution_string(&self, get_probs: fn(&CarStats) -> &[f64]) -> String {
+        let mut tmp_string = format!("{:12}", "car (driver)");
+
+        for position in 1..self.car_stats.len() + 1 {
+            write!(&mut tmp_string, ",{:>7}", format!("P{}", position)).unwrap();
+        }
+
+        writeln!(&mut tmp_string).unwrap();
+
+        for car_stats in self.car_stats.iter() {
+            write!(
+                &mut tmp_string,
+                "{:3} ({})   ",
+                car_stats.car_no, car_stats.driver_initials
+            )
+            .unwrap();
+
+            for position_prob in get_probs(car_stats).iter() {
+                write!(&mut tmp_string, ",{:>6.1}%", position_prob * 100.0).unwrap();
+            }
+
+            writeln!(&mut tmp_string).unwrap();
+        }
+
+        tmp_string
+    }
+
+    /// write_json writes the aggregated results to a JSON file.
+    pub fn write_json(&self, filepath: &Path) -> anyhow::Result<()> {
+        // open file
+        let fh = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(filepath)
+            .context(format!(
+                "Failed to open output file {}!",
+                filepath.to_str().unwrap()
+            ))?;
+
+        // write aggregated results
+        serde_json::to_writer_pretty(&fh, self).context(format!(
+            "Failed to write Monte Carlo result to file {}!",
+            filepath.to_str().unwrap()
+        ))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::McResult;
+    use crate::post::race_result::tests::create_race_result;
+    use crate::post::race_result::RaceResult;
+    use approx::assert_ulps_eq;
+
+    #[test]
+    fn test_mc_result_not_classified_run() {
+        // car 3 is not classified in the first run (5 of 10 laps) but still placed third, i.e. only
+        // the second run counts for its podium probability
+        let race_results = vec![
+            create_race_result(&[10, 10, 5]),
+            create_race_result(&[10, 10, 10]),
+        ];
+        let mc_result = McResult::new(&race_results);
+        let car_stats = mc_result.car_stats.iter().find(|x| x.car_no == 3).unwrap();
+
+        assert_eq!(mc_result.no_sim_runs, 2);
+        assert_ulps_eq!(car_stats.position_probs[2], 1.0);
+        assert_ulps_eq!(car_stats.p_win, 0.0);
+        assert_ulps_eq!(car_stats.p_podium, 0.5);
+        assert_ulps_eq!(car_stats.p_points, 0.5);
+        assert_ulps_eq!(car_stats.p_dnf, 0.5);
+        assert_ulps_eq!(car_stats.racetime_mean, 1020.0);
+    }
+    #[test]
+    fn test_mc_result_empty() {
+        let race_results: Vec<RaceResult> = vec![];
+        let mc_result = McResult::new(&race_results);
+
+        assert_eq!(mc_result.no_sim_runs, 0);
+        assert!(mc_result.car_stats.is_empty());
+    }
+}
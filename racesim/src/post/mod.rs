@@ -1 +1,2 @@
+pub mod mc_result;
 pub mod race_result;
@@ -1,20 +1,271 @@
+use crate::core::events::{RaceEvent, RaceEventKind};
+use crate::core::penalties::Penalty;
+use crate::core::pit_stops::PitStop;
+use crate::core::race::FcyPhaseType;
+use crate::core::regulations::RegulationViolation;
+use anyhow::Context;
+use serde::Serialize;
 use std::fmt::Write;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write as IoWrite};
+use std::path::Path;
 
 /// CarDriverPair is used to store car number and driver initials for post-processing the results.
+#[derive(Debug, Serialize)]
 pub struct CarDriverPair {
     pub car_no: u32,
     pub driver_initials: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationStatus {
+    Running,       // car is running (classification at the end of a lap)
+    Finished,      // car finished the race
+    Retired,       // car retired but is classified (completed at least 90% of the winner's laps)
+    NotClassified, // car is not classified (completed less than 90% of the winner's laps)
+}
+
+/// ClassificationEntry contains the classification of a car at the end of a lap or at the end of
+/// the race.
+///
+/// * `car_idx` - Index of the car (in the order of car_driver_pairs)
+/// * `car_no` - Car number
+/// * `position` - Position of the car
+/// * `compl_laps` - Number of completed laps
+/// * `racetime` - (s) Race time at the end of the last completed lap
+/// * `gap_leader` - (s) Gap to the leader (measured when crossing the finish line in the same lap)
+/// * `interval` - (s) Gap to the car ahead (measured when crossing the finish line in the same lap)
+/// * `laps_down` - Number of laps the car is behind the leader
+/// * `status` - Classification status of the car
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassificationEntry {
+    pub car_idx: usize,
+    pub car_no: u32,
+    pub position: u32,
+    pub compl_laps: u32,
+    pub racetime: f64,
+    pub gap_leader: f64,
+    pub interval: f64,
+    pub laps_down: u32,
+    pub status: ClassificationStatus,
+}
+
 /// RaceResult contains all race information that is required for post-processing the results.
+/// `compounds` contains the tire compound used in every lap (same indexing as the lap times),
+/// `sectortimes` contains the times of the three sectors of every lap (same indexing, zero if not
+/// available), `drs_point_times` contains the race times at which the cars passed the DRS
+/// measurement points in every lap (same indexing, zero if a point was not passed),
+/// `retirement_laps` contains the lap in which a car retired from the race (None if the car was
+/// not retired, i.e. it is not a DNF). `penalties` contains the penalties issued during the race,
+/// `pit_stops` contains the pit stops of all cars including their drawn standstill times.
+/// `t_penalties` contains the time penalties of every car that are added to its final race time
+/// after the race (e.g. due to `regulation_violations` or unserved `penalties`).
+#[derive(Debug, Serialize)]
 pub struct RaceResult {
+    pub seed: u64,
     pub tot_no_laps: u32,
     pub car_driver_pairs: Vec<CarDriverPair>,
     pub laptimes: Vec<Vec<f64>>,
     pub racetimes: Vec<Vec<f64>>,
+    pub compounds: Vec<Vec<String>>,
+    pub sectortimes: Vec<Vec<[f64; 3]>>,
+    pub drs_point_times: Vec<Vec<Vec<f64>>>,
+    pub retirement_laps: Vec<Option<u32>>,
+    pub race_events: Vec<RaceEvent>,
+    pub regulation_violations: Vec<RegulationViolation>,
+    pub penalties: Vec<Penalty>,
+    pub pit_stops: Vec<PitStop>,
+    pub t_penalties: Vec<f64>,
 }
 
 impl RaceResult {
+    /// get_compl_laps returns the number of laps the car at the inserted index completed in the
+    /// race.
+    pub fn get_compl_laps(&self, idx: usize) -> u32 {
+        self.racetimes[idx]
+            .iter()
+            .skip(1)
+            .take_while(|&&racetime| racetime > 0.0)
+            .count() as u32
+    }
+
+    /// get_final_racetime returns the race time of the car at the inserted index at the end of its
+    /// last completed lap (including time penalties that are added after the race).
+    pub fn get_final_racetime(&self, idx: usize) -> f64 {
+        self.get_classified_racetime(idx, self.get_compl_laps(idx))
+    }
+
+    /// get_classified_racetime returns the race time of the car at the inserted index at the end of
+    /// the inserted lap. Time penalties are added if it is the last lap completed by the car.
+    fn get_classified_racetime(&self, idx: usize, lap: u32) -> f64 {
+        if lap == self.get_compl_laps(idx) {
+            self.racetimes[idx][lap as usize] + self.t_penalties[idx]
+        } else {
+            self.racetimes[idx][lap as usize]
+        }
+    }
+
+    /// get_dnf checks if the car at the inserted index did not finish the race, i.e. retired.
+    pub fn get_dnf(&self, idx: usize) -> bool {
+        self.retirement_laps[idx].is_some()
+    }
+
+    /// get_lap_classification returns the classification at the end of the inserted lap (sorted by
+    /// position). Only those cars are considered that completed the lap, they are sorted by the
+    /// race time at which they crossed the finish line. Gaps and intervals are therefore also
+    /// measured at the finish line, i.e. a gap bigger than a lap time indicates a lapped car.
+    pub fn get_lap_classification(&self, lap: u32) -> Vec<ClassificationEntry> {
+        let mut idxs: Vec<usize> = (0..self.car_driver_pairs.len())
+            .filter(|&idx| self.get_compl_laps(idx) >= lap)
+            .collect();
+
+        idxs.sort_by(|&a, &b| {
+            self.racetimes[a][lap as usize]
+                .partial_cmp(&self.racetimes[b][lap as usize])
+                .unwrap()
+        });
+
+        let mut classification = Vec::with_capacity(idxs.len());
+
+        for (i, &idx) in idxs.iter().enumerate() {
+            let racetime = self.racetimes[idx][lap as usize];
+
+            classification.push(ClassificationEntry {
+                car_idx: idx,
+                car_no: self.car_driver_pairs[idx].car_no,
+                position: i as u32 + 1,
+                compl_laps: lap,
+                racetime,
+                gap_leader: racetime - self.racetimes[idxs[0]][lap as usize],
+                interval: if i > 0 {
+                    racetime - self.racetimes[idxs[i - 1]][lap as usize]
+                } else {
+                    0.0
+                },
+                laps_down: self.get_max_compl_laps_at(racetime) - lap,
+                status: ClassificationStatus::Running,
+            })
+        }
+
+        classification
+    }
+
+    /// get_final_classification returns the final classification of the race (sorted by position)
+    /// according to the FIA rules: cars are sorted by the number of completed laps and afterward by
+    /// their race times, independent of whether they retired or not. Cars that completed less than
+    /// 90% of the winner's laps (rounded down) are not classified and placed at the end. Gaps and
+    /// intervals are measured at the end of the last lap completed by the respective car.
+    pub fn get_final_classification(&self) -> Vec<ClassificationEntry> {
+        let no_cars = self.car_driver_pairs.len();
+        let max_compl_laps = (0..no_cars)
+            .map(|idx| self.get_compl_laps(idx))
+            .max()
+            .unwrap_or(0);
+        let min_compl_laps_classified = (max_compl_laps as f64 * 0.9).floor() as u32;
+
+        let mut idxs: Vec<usize> = (0..no_cars).collect();
+
+        idxs.sort_by(|&a, &b| {
+            (self.get_compl_laps(a) < min_compl_laps_classified)
+                .cmp(&(self.get_compl_laps(b) < min_compl_laps_classified))
+                .then(self.get_compl_laps(b).cmp(&self.get_compl_laps(a)))
+                .then(
+                    self.get_final_racetime(a)
+                        .partial_cmp(&self.get_final_racetime(b))
+                        .unwrap(),
+                )
+        });
+
+        let mut classification = Vec::with_capacity(no_cars);
+
+        for (i, &idx) in idxs.iter().enumerate() {
+            let compl_laps = self.get_compl_laps(idx);
+            let racetime = self.get_final_racetime(idx);
+
+            let status = if compl_laps < min_compl_laps_classified {
+                ClassificationStatus::NotClassified
+            } else if self.get_dnf(idx) {
+                ClassificationStatus::Retired
+            } else {
+                ClassificationStatus::Finished
+            };
+
+            classification.push(ClassificationEntry {
+                car_idx: idx,
+                car_no: self.car_driver_pairs[idx].car_no,
+                position: i as u32 + 1,
+                compl_laps,
+                racetime,
+                gap_leader: racetime - self.get_classified_racetime(idxs[0], compl_laps),
+                interval: if i > 0 {
+                    racetime - self.get_classified_racetime(idxs[i - 1], compl_laps)
+                } else {
+                    0.0
+                },
+                laps_down: max_compl_laps - compl_laps,
+                status,
+            })
+        }
+
+        classification
+    }
+
+    /// get_final_positions returns the final positions of the cars (in the order of
+    /// car_driver_pairs) according to the final classification.
+    pub fn get_final_positions(&self) -> Vec<u32> {
+        let mut positions = vec![0; self.car_driver_pairs.len()];
+
+        for entry in self.get_final_classification().iter() {
+            positions[entry.car_idx] = entry.position;
+        }
+
+        positions
+    }
+
+    /// get_position_history returns the positions of the cars at the end of every lap (same
+    /// indexing as the lap times, i.e. the first entry is zero, zero is also used for laps that
+    /// were not completed).
+    pub fn get_position_history(&self) -> Vec<Vec<u32>> {
+        let mut positions =
+            vec![vec![0; self.tot_no_laps as usize + 1]; self.car_driver_pairs.len()];
+
+        for lap in 1..self.tot_no_laps + 1 {
+            for entry in self.get_lap_classification(lap).iter() {
+                positions[entry.car_idx][lap as usize] = entry.position;
+            }
+        }
+
+        positions
+    }
+
+    /// get_max_compl_laps_at returns the maximum number of laps any car completed until the
+    /// inserted race time.
+    fn get_max_compl_laps_at(&self, racetime: f64) -> u32 {
+        self.racetimes
+            .iter()
+            .map(|racetimes_car| {
+                racetimes_car
+                    .iter()
+                    .skip(1)
+                    .take_while(|&&x| x > 0.0 && x <= racetime)
+                    .count() as u32
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// get_fcy_phase_occurred checks if an FCY phase of the inserted type was started during the
+    /// race.
+    pub fn get_fcy_phase_occurred(&self, phase_type: &FcyPhaseType) -> bool {
+        self.race_events
+            .iter()
+            .any(|race_event| match &race_event.kind {
+                RaceEventKind::FcyStart(x) => x == phase_type,
+                _ => false,
+            })
+    }
+
     /// print_lap_and_race_times prints the resulting lap and race times to the console output.
     pub fn print_lap_and_race_times(&self) {
         // create string for lap times and race times
@@ -70,5 +321,693 @@ impl RaceResult {
         println!("RESULT: Race times");
         println!("{}", tmp_string_car_driver_info);
         println!("{}", tmp_string_racetime);
+
+        self.print_final_classification();
+    }
+
+    /// get_drs_point_intervals returns the interval of every car to the car that passed the DRS
+    /// measurement points directly before it (independent of the lap, i.e. as used to determine
+    /// DRS eligibility). The intervals have the same indexing as drs_point_times, they are zero if
+    /// a point was not passed and infinite if no car passed it before.
+    pub fn get_drs_point_intervals(&self) -> Vec<Vec<Vec<f64>>> {
+        let mut intervals: Vec<Vec<Vec<f64>>> = self
+            .drs_point_times
+            .iter()
+            .map(|x| x.iter().map(|y| vec![0.0; y.len()]).collect())
+            .collect();
+        let no_drs_points = self
+            .drs_point_times
+            .first()
+            .and_then(|x| x.first())
+            .map_or(0, |x| x.len());
+
+        for j in 0..no_drs_points {
+            // collect and sort the race times of all cars at the current point
+            let mut passings: Vec<(f64, usize, usize)> = vec![];
+
+            for (i, drs_point_times_car) in self.drs_point_times.iter().enumerate() {
+                for (lap, drs_point_times_lap) in drs_point_times_car.iter().enumerate().skip(1) {
+                    if drs_point_times_lap[j] > 0.0 {
+                        passings.push((drs_point_times_lap[j], i, lap))
+                    }
+                }
+            }
+
+            passings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+            for (k, &(t_passed, i, lap)) in passings.iter().enumerate() {
+                intervals[i][lap][j] = if k > 0 {
+                    t_passed - passings[k - 1].0
+                } else {
+                    f64::INFINITY
+                };
+            }
+        }
+
+        intervals
+    }
+
+    /// print_sector_times prints the best sector times of every car (including the lap in which
+    /// they were achieved), the resulting ideal lap time, and the mean and minimum intervals to the
+    /// car in front at the DRS measurement points to the console output.
+    pub fn print_sector_times(&self) {
+        let mut tmp_string_sectors = format!(
+            "{:12}, {:>14}, {:>14}, {:>14}, {:>10}, {:>10}\n",
+            "car (driver)",
+            "best s1 (lap)",
+            "best s2 (lap)",
+            "best s3 (lap)",
+            "ideal lap",
+            "best lap"
+        );
+
+        for (i, car_driver_pair) in self.car_driver_pairs.iter().enumerate() {
+            write!(
+                &mut tmp_string_sectors,
+                "{:3} ({})   ",
+                car_driver_pair.car_no, car_driver_pair.driver_initials
+            )
+            .unwrap();
+
+            let mut t_ideal_lap = 0.0;
+
+            for sector in 0..3 {
+                let best_sector = self.sectortimes[i]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, x)| x[sector] > 0.0)
+                    .min_by(|a, b| a.1[sector].partial_cmp(&b.1[sector]).unwrap());
+
+                match best_sector {
+                    Some((lap, x)) => {
+                        write!(&mut tmp_string_sectors, ", {:7.3}s ({:3})", x[sector], lap)
+                            .unwrap();
+                        t_ideal_lap += x[sector];
+                    }
+                    None => {
+                        write!(&mut tmp_string_sectors, ", {:>14}", "-").unwrap();
+                        t_ideal_lap = f64::NAN;
+                    }
+                }
+            }
+
+            let best_lap = self.laptimes[i]
+                .iter()
+                .filter(|&&x| x > 0.0)
+                .fold(f64::INFINITY, |a, &b| a.min(b));
+
+            writeln!(
+                &mut tmp_string_sectors,
+                ", {:>10}, {:>10}",
+                format_time(t_ideal_lap),
+                format_time(best_lap)
+            )
+            .unwrap();
+        }
+
+        // create string for the intervals at the DRS measurement points (mean and minimum over
+        // all laps in which the car was not the first to pass the point)
+        let intervals = self.get_drs_point_intervals();
+        let no_drs_points = intervals
+            .first()
+            .and_then(|x| x.first())
+            .map_or(0, |x| x.len());
+        let mut tmp_string_drs = format!("{:12}", "car (driver)");
+
+        for j in 0..no_drs_points {
+            write!(
+                &mut tmp_string_drs,
+                ", {:>10}, {:>10}",
+                format!("p{} mean", j + 1),
+                format!("p{} min", j + 1)
+            )
+            .unwrap();
+        }
+
+        writeln!(&mut tmp_string_drs).unwrap();
+
+        for (i, car_driver_pair) in self.car_driver_pairs.iter().enumerate() {
+            write!(
+                &mut tmp_string_drs,
+                "{:3} ({})   ",
+                car_driver_pair.car_no, car_driver_pair.driver_initials
+            )
+            .unwrap();
+
+            for j in 0..no_drs_points {
+                let intervals_car: Vec<f64> = intervals[i]
+                    .iter()
+                    .map(|x| x[j])
+                    .filter(|&x| x > 0.0 && x.is_finite())
+                    .collect();
+                let interval_mean = intervals_car.iter().sum::<f64>() / intervals_car.len() as f64;
+                let interval_min = intervals_car.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+
+                write!(
+                    &mut tmp_string_drs,
+                    ", {:>10}, {:>10}",
+                    format_time(interval_mean),
+                    format_time(interval_min)
+                )
+                .unwrap();
+            }
+
+            writeln!(&mut tmp_string_drs).unwrap();
+        }
+
+        println!("RESULT: Sector times");
+        println!("{}", tmp_string_sectors);
+
+        println!("RESULT: Intervals at the DRS measurement points");
+        println!("{}", tmp_string_drs);
+    }
+
+    /// print_final_classification prints the final classification to the console output.
+    pub fn print_final_classification(&self) {
+        let mut tmp_string_classification = format!(
+            "{:>3}, {:12}, {:>4}, {:>10}, {:>10}, {:>10}, {}\n",
+            "pos", "car (driver)", "laps", "race time", "gap", "interval", "status"
+        );
+
+        let classification = self.get_final_classification();
+
+        for (i, entry) in classification.iter().enumerate() {
+            // show gap and interval in laps if the cars completed a different number of laps
+            let gap_leader = format_gap(entry.gap_leader, entry.laps_down);
+            let interval = if i > 0 {
+                format_gap(
+                    entry.interval,
+                    classification[i - 1].compl_laps - entry.compl_laps,
+                )
+            } else {
+                format_gap(entry.interval, 0)
+            };
+
+            let status = match entry.status {
+                ClassificationStatus::Running | ClassificationStatus::Finished => "",
+                ClassificationStatus::Retired => "DNF",
+                ClassificationStatus::NotClassified => "NC",
+            };
+
+            writeln!(
+                &mut tmp_string_classification,
+                "{:3}, {:3} ({})   , {:4}, {:9.3}s, {:>10}, {:>10}, {}",
+                entry.position,
+                entry.car_no,
+                self.car_driver_pairs[entry.car_idx].driver_initials,
+                entry.compl_laps,
+                entry.racetime,
+                gap_leader,
+                interval,
+                status
+            )
+            .unwrap();
+        }
+
+        println!("RESULT: Final classification");
+        println!("{}", tmp_string_classification);
+    }
+
+    /// print_race_events prints the recorded race events to the console output. The frequent events
+    /// on the track (e.g. overtakes and pit entries) are only printed if the complete event log is
+    /// requested.
+    pub fn print_race_events(&self, print_all: bool) {
+        let mut tmp_string_events = String::from("race time, lap, car, event\n");
+
+        for race_event in self.race_events.iter() {
+            if !print_all && race_event.kind.get_on_track() {
+                continue;
+            }
+
+            let car_no = match race_event.car_no {
+                Some(car_no) => car_no.to_string(),
+                None => String::from("-"),
+            };
+
+            writeln!(
+                &mut tmp_string_events,
+                "{:8.3}s, {:3}, {:>3}, {:?}",
+                race_event.t_race, race_event.lap, car_no, race_event.kind
+            )
+            .unwrap();
+        }
+
+        println!("RESULT: Race events");
+        println!("{}", tmp_string_events);
+    }
+
+    /// print_regulation_violations prints the violations of the regulations and the resulting time
+    /// penalties to the console output.
+    pub fn print_regulation_violations(&self) {
+        let mut tmp_string_violations = String::from("car, penalty, violation\n");
+
+        for violation in self.regulation_violations.iter() {
+            writeln!(
+                &mut tmp_string_violations,
+                "{:3}, {:6.3}s, {}",
+                violation.car_no, violation.t_penalty, violation.kind
+            )
+            .unwrap();
+        }
+
+        println!("RESULT: Regulation violations");
+        println!("{}", tmp_string_violations);
+    }
+
+    /// print_penalties prints the penalties issued during the race to the console output, i.e.
+    /// when they were issued, when they were served, and the time added to the final race time if
+    /// they were not served.
+    pub fn print_penalties(&self) {
+        let mut tmp_string_penalties =
+            String::from("car, penalty type, lap issued, lap served, time added\n");
+
+        for penalty in self.penalties.iter() {
+            let lap_served = match penalty.lap_served {
+                Some(lap_served) => lap_served.to_string(),
+                None => String::from("-"),
+            };
+
+            writeln!(
+                &mut tmp_string_penalties,
+                "{:3}, {:12}, {:10}, {:>10}, {:8.3}s",
+                penalty.car_no,
+                format!("{:?}", penalty.penalty_type),
+                penalty.lap_issued,
+                lap_served,
+                penalty.t_added
+            )
+            .unwrap();
+        }
+
+        println!("RESULT: Penalties");
+        println!("{}", tmp_string_penalties);
+    }
+
+    /// print_pit_stops prints the pit stops of all cars to the console output, i.e. their drawn
+    /// tire change and total standstill times as well as the queueing delays in the pit lane.
+    pub fn print_pit_stops(&self) {
+        let mut tmp_string_pit_stops = String::from(
+            "car, inlap,  race time, tire change, standstill,   queue, release hold, slow stop, \
+            unsafe release\n",
+        );
+
+        for pit_stop in self.pit_stops.iter() {
+            writeln!(
+                &mut tmp_string_pit_stops,
+                "{:3}, {:5}, {:9.3}s, {:10.3}s, {:9.3}s, {:6.3}s, {:11.3}s, {:>9}, {:>14}",
+                pit_stop.car_no,
+                pit_stop.inlap,
+                pit_stop.t_race,
+                pit_stop.t_tirechange,
+                pit_stop.t_standstill,
+                pit_stop.t_queue,
+                pit_stop.t_release_hold,
+                if pit_stop.slow_stop { "yes" } else { "no" },
+                if pit_stop.unsafe_release { "yes" } else { "no" }
+            )
+            .unwrap();
+        }
+
+        println!("RESULT: Pit stops");
+        println!("{}", tmp_string_pit_stops);
+    }
+}
+
+/// OutputFormat determines the file format used to export race results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+}
+
+impl OutputFormat {
+    /// from_path determines the output format on the basis of the file extension of the inserted
+    /// path (None if the extension is not supported).
+    pub fn from_path(filepath: &Path) -> Option<OutputFormat> {
+        match filepath.extension().and_then(|x| x.to_str()) {
+            Some("csv") => Some(OutputFormat::Csv),
+            Some("json") => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// RaceResultExport is used to export a race result together with its run index and the computed
+/// classifications.
+#[derive(Debug, Serialize)]
+struct RaceResultExport<'a> {
+    run_idx: usize,
+    #[serde(flatten)]
+    race_result: &'a RaceResult,
+    positions: Vec<Vec<u32>>,
+    final_classification: Vec<ClassificationEntry>,
+}
+
+/// write_race_results writes the inserted race results to a file. The format (CSV or JSON) is
+/// determined by the file extension. The index of a race result in the inserted slice is written
+/// as its run index, together with its seed such that every run can be replayed. The CSV file is
+/// written in long format, i.e. with a row per run, car, and completed lap.
+pub fn write_race_results(race_results: &[RaceResult], filepath: &Path) -> anyhow::Result<()> {
+    let output_format = OutputFormat::from_path(filepath).context(format!(
+        "Unsupported file extension of output file {} (must be csv or json)!",
+        filepath.to_str().unwrap()
+    ))?;
+
+    // open file
+    let fh = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(filepath)
+        .context(format!(
+            "Failed to open output file {}!",
+            filepath.to_str().unwrap()
+        ))?;
+    let mut writer = BufWriter::new(fh);
+
+    // write race results
+    match output_format {
+        OutputFormat::Csv => write_csv(&mut writer, race_results).context(format!(
+            "Failed to write race results to file {}!",
+            filepath.to_str().unwrap()
+        ))?,
+        OutputFormat::Json => {
+            let race_results_export: Vec<RaceResultExport> = race_results
+                .iter()
+                .enumerate()
+                .map(|(run_idx, race_result)| RaceResultExport {
+                    run_idx,
+                    race_result,
+                    positions: race_result.get_position_history(),
+                    final_classification: race_result.get_final_classification(),
+                })
+                .collect();
+
+            serde_json::to_writer_pretty(&mut writer, &race_results_export).context(format!(
+                "Failed to write race results to file {}!",
+                filepath.to_str().unwrap()
+            ))?
+        }
+    }
+
+    writer.flush().context(format!(
+        "Failed to write race results to file {}!",
+        filepath.to_str().unwrap()
+    ))?;
+    Ok(())
+}
+
+/// RaceEventExport is used to export a race event together with the run index and the seed of its
+/// race.
+#[derive(Debug, Serialize)]
+struct RaceEventExport<'a> {
+    run_idx: usize,
+    seed: u64,
+    #[serde(flatten)]
+    race_event: &'a RaceEvent,
+}
+
+/// write_race_events writes the race events of the inserted race results to a file in the JSON
+/// lines format, i.e. one JSON object per line and event. Every event is written with the run
+/// index and the seed of its race.
+pub fn write_race_events(race_results: &[RaceResult], filepath: &Path) -> anyhow::Result<()> {
+    // open file
+    let fh = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(filepath)
+        .context(format!(
+            "Failed to open race event file {}!",
+            filepath.to_str().unwrap()
+        ))?;
+
+    // write race events
+    write_jsonl(&mut BufWriter::new(fh), race_results).context(format!(
+        "Failed to write race events to file {}!",
+        filepath.to_str().unwrap()
+    ))?;
+    Ok(())
+}
+
+/// write_jsonl writes the race events of the inserted race results in the JSON lines format to the
+/// inserted writer.
+fn write_jsonl<W: IoWrite>(writer: &mut W, race_results: &[RaceResult]) -> anyhow::Result<()> {
+    for (run_idx, race_result) in race_results.iter().enumerate() {
+        for race_event in race_result.race_events.iter() {
+            serde_json::to_writer(
+                &mut *writer,
+                &RaceEventExport {
+                    run_idx,
+                    seed: race_result.seed,
+                    race_event,
+                },
+            )?;
+            writeln!(writer)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// write_csv writes the inserted race results in long format to the inserted writer.
+fn write_csv<W: IoWrite>(writer: &mut W, race_results: &[RaceResult]) -> std::io::Result<()> {
+    // the number of DRS measurement points is the same for all runs of a race
+    let no_drs_points = race_results
+        .first()
+        .and_then(|x| x.drs_point_times.first())
+        .and_then(|x| x.first())
+        .map_or(0, |x| x.len());
+
+    write!(
+        writer,
+        "run,seed,car,driver,lap,laptime,racetime,position,compound,sector1,sector2,sector3"
+    )?;
+
+    for j in 0..no_drs_points {
+        write!(writer, ",racetime_drs_point{}", j + 1)?;
+    }
+
+    writeln!(writer)?;
+
+    for (run_idx, race_result) in race_results.iter().enumerate() {
+        let positions = race_result.get_position_history();
+
+        for (i, car_driver_pair) in race_result.car_driver_pairs.iter().enumerate() {
+            let compl_laps = race_result.get_compl_laps(i) as usize;
+
+            for (lap, position) in positions[i].iter().enumerate().take(compl_laps + 1).skip(1) {
+                let sectortimes = race_result.sectortimes[i][lap];
+
+                write!(
+                    writer,
+                    "{},{},{},{},{},{:.3},{:.3},{},{},{:.3},{:.3},{:.3}",
+                    run_idx,
+                    race_result.seed,
+                    car_driver_pair.car_no,
+                    car_driver_pair.driver_initials,
+                    lap,
+                    race_result.laptimes[i][lap],
+                    race_result.racetimes[i][lap],
+                    position,
+                    race_result.compounds[i][lap],
+                    sectortimes[0],
+                    sectortimes[1],
+                    sectortimes[2]
+                )?;
+
+                for drs_point_time in race_result.drs_point_times[i][lap].iter() {
+                    write!(writer, ",{:.3}", drs_point_time)?;
+                }
+
+                writeln!(writer)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// format_time returns a string representation of a time, which is "-" if the time is not
+/// available (e.g. the best lap of a car that retired before completing a lap).
+fn format_time(t: f64) -> String {
+    if t.is_finite() {
+        format!("{:.3}s", t)
+    } else {
+        String::from("-")
+    }
+}
+
+/// format_gap returns a string representation of a gap, which is given in laps if the difference in
+/// completed laps is greater than zero.
+fn format_gap(gap: f64, delta_laps: u32) -> String {
+    match delta_laps {
+        0 => format!("{:.3}s", gap),
+        1 => String::from("+1 lap"),
+        _ => format!("+{} laps", delta_laps),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{write_jsonl, CarDriverPair, ClassificationStatus, RaceResult};
+    use crate::core::events::{RaceEvent, RaceEventKind};
+    use crate::core::penalties::PenaltyType;
+    use crate::core::race::FcyPhaseType;
+    use serde::Deserialize;
+
+    /// create_race_result creates a race result of 10 laps with a lap time of 100s for every car and
+    /// the inserted numbers of completed laps (cars that did not complete all laps retired).
+    pub fn create_race_result(compl_laps: &[usize]) -> RaceResult {
+        let tot_no_laps = 10;
+        let no_cars = compl_laps.len();
+
+        let mut laptimes = vec![vec![0.0; tot_no_laps + 1]; no_cars];
+        let mut racetimes = vec![vec![0.0; tot_no_laps + 1]; no_cars];
+
+        for (i, &compl_laps_car) in compl_laps.iter().enumerate() {
+            for lap in 1..=compl_laps_car {
+                // cars with a higher index are slightly slower
+                laptimes[i][lap] = 100.0 + i as f64;
+                racetimes[i][lap] = racetimes[i][lap - 1] + laptimes[i][lap];
+            }
+        }
+
+        RaceResult {
+            seed: 0,
+            tot_no_laps: tot_no_laps as u32,
+            car_driver_pairs: (0..no_cars)
+                .map(|i| CarDriverPair {
+                    car_no: i as u32 + 1,
+                    driver_initials: String::from("XXX"),
+                })
+                .collect(),
+            laptimes,
+            racetimes,
+            compounds: vec![vec![]; no_cars],
+            sectortimes: vec![vec![[0.0; 3]; tot_no_laps + 1]; no_cars],
+            drs_point_times: vec![vec![vec![]; tot_no_laps + 1]; no_cars],
+            retirement_laps: compl_laps
+                .iter()
+                .map(|&x| {
+                    if x < tot_no_laps {
+                        Some(x as u32 + 1)
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            race_events: vec![],
+            regulation_violations: vec![],
+            penalties: vec![],
+            pit_stops: vec![],
+            t_penalties: vec![0.0; no_cars],
+        }
+    }
+
+    #[test]
+    fn test_final_classification_90_percent_rule() {
+        // car 1 finishes, car 2 retires in the last lap (9 of 10 laps completed -> classified), car
+        // 3 retires in the middle of the race (5 of 10 laps completed -> not classified)
+        let race_result = create_race_result(&[10, 9, 5]);
+        let classification = race_result.get_final_classification();
+
+        assert_eq!(
+            classification
+                .iter()
+                .map(|x| x.car_no)
+                .collect::<Vec<u32>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(classification[0].status, ClassificationStatus::Finished);
+        assert_eq!(classification[1].status, ClassificationStatus::Retired);
+        assert_eq!(
+            classification[2].status,
+            ClassificationStatus::NotClassified
+        );
+        assert_eq!(classification[1].laps_down, 1);
+        assert_eq!(classification[2].laps_down, 5);
+    }
+    #[test]
+    fn test_final_classification_nc_first_car() {
+        // 8 of 10 laps are less than 90% of the winner's laps -> the first car is not classified and
+        // placed at the end
+        let race_result = create_race_result(&[8, 10, 9]);
+        let classification = race_result.get_final_classification();
+
+        assert_eq!(
+            classification
+                .iter()
+                .map(|x| x.car_no)
+                .collect::<Vec<u32>>(),
+            vec![2, 3, 1]
+        );
+        assert_eq!(
+            classification[2].status,
+            ClassificationStatus::NotClassified
+        );
+        assert_eq!(race_result.get_final_positions(), vec![3, 1, 2]);
+    }
+    #[test]
+    fn test_race_events_jsonl_round_trip() {
+        #[derive(Deserialize)]
+        struct RaceEventImport {
+            run_idx: usize,
+            seed: u64,
+            #[serde(flatten)]
+            race_event: RaceEvent,
+        }
+
+        let mut race_results = vec![create_race_result(&[10, 10]), create_race_result(&[10, 4])];
+        race_results[1].seed = 7;
+        race_results[0].race_events = vec![
+            RaceEvent {
+                t_race: 150.5,
+                lap: 2,
+                car_no: Some(2),
+                kind: RaceEventKind::Overtake(1),
+            },
+            RaceEvent {
+                t_race: 420.25,
+                lap: 5,
+                car_no: None,
+                kind: RaceEventKind::FcyStart(FcyPhaseType::Vsc),
+            },
+        ];
+        race_results[1].race_events = vec![
+            RaceEvent {
+                t_race: 410.0,
+                lap: 5,
+                car_no: Some(2),
+                kind: RaceEventKind::Penalty(PenaltyType::DriveThrough),
+            },
+            RaceEvent {
+                t_race: 450.0,
+                lap: 5,
+                car_no: Some(2),
+                kind: RaceEventKind::Retirement,
+            },
+        ];
+
+        let mut buffer = vec![];
+        write_jsonl(&mut buffer, &race_results).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&buffer).unwrap().lines().collect();
+        assert_eq!(lines.len(), 4);
+
+        let race_events_imported: Vec<RaceEventImport> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        for (i, race_event_imported) in race_events_imported.iter().enumerate() {
+            let race_result = &race_results[i / 2];
+            assert_eq!(race_event_imported.run_idx, i / 2);
+            assert_eq!(race_event_imported.seed, race_result.seed);
+            assert_eq!(
+                race_event_imported.race_event,
+                race_result.race_events[i % 2]
+            );
+        }
     }
 }
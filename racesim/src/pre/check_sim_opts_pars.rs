@@ -1,3 +1,8 @@
+use crate::core::race::F_LAPTIME_SC_CATCH_UP;
+use crate::core::regulations::{Enforcement, Regulations};
+use crate::core::strategy_controller::StrategyControllerPars;
+use crate::core::tireset::TiresetPars;
+use crate::post::race_result::OutputFormat;
 use crate::pre::read_sim_pars::SimPars;
 use crate::pre::sim_opts::SimOpts;
 use anyhow::Context;
@@ -22,6 +27,31 @@ pub fn check_sim_opts_pars(sim_opts: &SimOpts, sim_pars: &SimPars) -> anyhow::Re
         ));
     }
 
+    if let Some(output_path) = sim_opts.output_path.as_ref() {
+        if OutputFormat::from_path(output_path).is_none() {
+            return Err(InputValueError)
+                .context("The file extension of the output path must be csv or json!");
+        }
+    }
+
+    if sim_opts.telemetry_path.is_some() && (sim_opts.no_sim_runs != 1 || sim_opts.subcmd.is_some())
+    {
+        return Err(InputValueError).context(
+            "Telemetry can only be recorded for a single simulation run without a subcommand!",
+        );
+    }
+
+    if sim_opts.telemetry_decimation < 1 {
+        return Err(InputValueError).context(format!(
+            "telemetry_decimation must be at least equal to one, but is {}!",
+            sim_opts.telemetry_decimation
+        ));
+    }
+
+    if sim_opts.gui && sim_opts.subcmd.is_some() {
+        return Err(InputValueError).context("gui cannot be activated together with a subcommand!");
+    }
+
     if sim_opts.gui && sim_opts.no_sim_runs != 1 {
         return Err(InputValueError)
             .context("If gui is activated, no_sim_runs must be equal to one!");
@@ -35,6 +65,237 @@ pub fn check_sim_opts_pars(sim_opts: &SimOpts, sim_pars: &SimPars) -> anyhow::Re
     }
 
     // PART 2: SIMULATION PARAMETERS
+    // RACE ----------------------------------------------------------------------------------------
+    if sim_pars.race_pars.min_t_dist_sc <= 0.0 {
+        return Err(InputValueError).context("min_t_dist_sc must be positive!");
+    }
+
+    if sim_pars.race_pars.f_laptime_sc <= 1.0 + F_LAPTIME_SC_CATCH_UP {
+        return Err(InputValueError).context(format!(
+            "f_laptime_sc must be greater than {:.1} such that the cars can catch up with the \
+            safety car queue without driving faster than at race pace!",
+            1.0 + F_LAPTIME_SC_CATCH_UP
+        ));
+    }
+
+    if sim_pars.race_pars.f_laptime_vsc <= 1.0 {
+        return Err(InputValueError).context("f_laptime_vsc must be greater than 1.0!");
+    }
+
+    if sim_pars.race_pars.t_vsc_ending < 0.0 {
+        return Err(InputValueError).context("t_vsc_ending must not be negative!");
+    }
+
+    if sim_pars.race_pars.t_overtake_loser < 0.0
+        || sim_pars.race_pars.t_dirty_air < 0.0
+        || sim_pars.race_pars.f_degr_dirty_air < 0.0
+    {
+        return Err(InputValueError)
+            .context("t_overtake_loser, t_dirty_air, and f_degr_dirty_air must not be negative!");
+    }
+
+    if sim_pars.race_pars.dirty_air_window < 0.0 {
+        return Err(InputValueError).context("dirty_air_window must not be negative!");
+    }
+
+    let mut fcy_phases = sim_pars.race_pars.fcy_phases.to_owned();
+    fcy_phases.sort_by(|a, b| a.t_start.partial_cmp(&b.t_start).unwrap());
+
+    for (i, fcy_phase) in fcy_phases.iter().enumerate() {
+        if fcy_phase.t_start < 0.0 || fcy_phase.t_end <= fcy_phase.t_start {
+            return Err(InputValueError).context(format!(
+                "The FCY phase starting at {:.3}s does not fulfill the requirements (t_start not \
+                negative, t_end greater than t_start)!",
+                fcy_phase.t_start
+            ));
+        }
+
+        if i > 0 && fcy_phase.t_start <= fcy_phases[i - 1].t_end {
+            return Err(InputValueError).context(format!(
+                "The FCY phase starting at {:.3}s overlaps with the previous phase!",
+                fcy_phase.t_start
+            ));
+        }
+    }
+
+    if !(0.0..=1.0).contains(&sim_pars.race_pars.p_failure) {
+        return Err(InputValueError)
+            .context("p_failure is not within the required range [0.0, 1.0]!");
+    }
+
+    let event_pars = &sim_pars.race_pars.event_pars;
+
+    if !(0.0..=1.0).contains(&event_pars.p_sc_accident)
+        || !(0.0..=1.0).contains(&event_pars.p_vsc_failure)
+    {
+        return Err(InputValueError).context(
+            "p_sc_accident and p_vsc_failure must be within the required range [0.0, 1.0]!",
+        );
+    }
+
+    if event_pars.sc_duration_laps[0] < 1
+        || event_pars.sc_duration_laps[1] < event_pars.sc_duration_laps[0]
+    {
+        return Err(InputValueError).context(
+            "sc_duration_laps does not fulfill the requirements (min at least 1, max not smaller \
+            than min)!",
+        );
+    }
+
+    if event_pars.vsc_duration[0] <= 0.0 || event_pars.vsc_duration[1] < event_pars.vsc_duration[0]
+    {
+        return Err(InputValueError).context(
+            "vsc_duration does not fulfill the requirements (min greater than 0.0, max not smaller \
+            than min)!",
+        );
+    }
+
+    if sim_pars.race_pars.regulation_pars.t_penalty < 0.0 {
+        return Err(InputValueError).context("t_penalty must not be negative!");
+    }
+
+    let penalty_pars = &sim_pars.race_pars.penalty_pars;
+
+    if !(0.0..=1.0).contains(&penalty_pars.p_penalty)
+        || penalty_pars
+            .p_penalty_types
+            .iter()
+            .any(|p| !(0.0..=1.0).contains(p))
+        || (penalty_pars.p_penalty_types.iter().sum::<f64>() - 1.0).abs() > 1e-6
+    {
+        return Err(InputValueError).context(
+            "The penalty probabilities do not fulfill the requirements (p_penalty and \
+            p_penalty_types within [0.0, 1.0], p_penalty_types sum up to 1.0)!",
+        );
+    }
+
+    if penalty_pars.t_standstill_stop_and_go < 0.0
+        || penalty_pars.t_drive_through_unserved < 0.0
+        || penalty_pars.t_stop_and_go_unserved < 0.0
+    {
+        return Err(InputValueError).context(
+            "t_standstill_stop_and_go, t_drive_through_unserved, and t_stop_and_go_unserved must \
+            not be negative!",
+        );
+    }
+
+    if let Some(weather_pars) = sim_pars.race_pars.weather_pars.as_ref() {
+        if weather_pars.wetness.is_empty()
+            || weather_pars
+                .wetness
+                .iter()
+                .any(|x| x[0] < 0.0 || !(0.0..=1.0).contains(&x[1]))
+        {
+            return Err(InputValueError).context(
+                "The wetness timeline does not fulfill the requirements (at least one point, race \
+                times not negative, wetness within [0.0, 1.0])!",
+            );
+        }
+
+        for (compound, wetness_pars) in weather_pars.compound_pars.iter() {
+            if !(0.0..=1.0).contains(&wetness_pars.wetness_range[0])
+                || !(0.0..=1.0).contains(&wetness_pars.wetness_range[1])
+                || wetness_pars.wetness_range[1] < wetness_pars.wetness_range[0]
+                || wetness_pars.k_wetness < 0.0
+                || wetness_pars.k_overheating < 0.0
+            {
+                return Err(InputValueError).context(format!(
+                    "The wetness parameters of compound {} do not fulfill the requirements \
+                    (wetness range within [0.0, 1.0], max not smaller than min, k_wetness and \
+                    k_overheating not negative)!",
+                    compound
+                ));
+            }
+        }
+    }
+
+    if let Some(team_order_pars) = sim_pars.race_pars.team_order_pars.as_ref() {
+        if team_order_pars.t_pace_delta_min < 0.0 {
+            return Err(InputValueError).context("t_pace_delta_min must not be negative!");
+        }
+
+        if team_order_pars.max_gap <= 0.0 {
+            return Err(InputValueError).context("max_gap must be positive!");
+        }
+
+        if team_order_pars.lap_window[1] < team_order_pars.lap_window[0] {
+            return Err(InputValueError)
+                .context("The last lap of the team order lap window is before the first lap!");
+        }
+    }
+
+    if let Some(blue_flag_pars) = sim_pars.race_pars.blue_flag_pars.as_ref() {
+        if blue_flag_pars.blue_flag_window <= 0.0 {
+            return Err(InputValueError).context("blue_flag_window must be positive!");
+        }
+
+        if blue_flag_pars.s_yield < 0.0 {
+            return Err(InputValueError).context("s_yield must not be negative!");
+        }
+
+        if blue_flag_pars.t_yield < 0.0 {
+            return Err(InputValueError).context("t_yield must not be negative!");
+        }
+    }
+
+    for (team, pit_stop_pars) in sim_pars.race_pars.pit_stop_pars.iter() {
+        if pit_stop_pars.t_tirechange_sigma < 0.0 {
+            return Err(InputValueError).context(format!(
+                "t_tirechange_sigma of team {} must not be negative!",
+                team
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&pit_stop_pars.p_slow_stop) {
+            return Err(InputValueError).context(format!(
+                "p_slow_stop of team {} is not within the required range [0.0, 1.0]!",
+                team
+            ));
+        }
+
+        if pit_stop_pars.t_slow_stop_min <= 0.0 || pit_stop_pars.k_slow_stop <= 1.0 {
+            return Err(InputValueError).context(format!(
+                "t_slow_stop_min of team {} must be positive and k_slow_stop must be greater than \
+                1.0!",
+                team
+            ));
+        }
+    }
+
+    if let Some(pit_lane_pars) = sim_pars.race_pars.pit_lane_pars.as_ref() {
+        if pit_lane_pars.t_release_gap < 0.0 {
+            return Err(InputValueError).context("t_release_gap must not be negative!");
+        }
+
+        if !(0.0..=1.0).contains(&pit_lane_pars.p_unsafe_release) {
+            return Err(InputValueError)
+                .context("p_unsafe_release is not within the required range [0.0, 1.0]!");
+        }
+    }
+
+    if let Some(start_pars) = sim_pars.race_pars.start_pars.as_ref() {
+        if start_pars.t_loss_dirty_side < 0.0 {
+            return Err(InputValueError).context("t_loss_dirty_side must not be negative!");
+        }
+
+        if !(0.0..=1.0).contains(&start_pars.p_incident_turn_1) {
+            return Err(InputValueError)
+                .context("p_incident_turn_1 is not within the required range [0.0, 1.0]!");
+        }
+
+        if start_pars.t_loss_incident[0] < 0.0
+            || start_pars.t_loss_incident[1] < start_pars.t_loss_incident[0]
+        {
+            return Err(InputValueError)
+                .context("t_loss_incident must be a non-negative range (min, max)!");
+        }
+
+        if !(0.0..=1.0).contains(&start_pars.p_dnf_incident) {
+            return Err(InputValueError)
+                .context("p_dnf_incident is not within the required range [0.0, 1.0]!");
+        }
+    }
+
     // TRACK ---------------------------------------------------------------------------------------
     if sim_pars.track_pars.s12 <= 0.0 || sim_pars.track_pars.length <= sim_pars.track_pars.s12 {
         return Err(InputValueError)
@@ -74,6 +335,162 @@ pub fn check_sim_opts_pars(sim_opts: &SimOpts, sim_pars: &SimPars) -> anyhow::Re
         );
     }
 
+    if let Some(overtaking_difficulty) = sim_pars.track_pars.overtaking_difficulty {
+        if overtaking_difficulty <= 0.0 {
+            return Err(InputValueError).context("overtaking_difficulty must be positive!");
+        }
+    }
+
+    if sim_pars.track_pars.k_vel_max < 0.0 {
+        return Err(InputValueError).context("k_vel_max must not be negative!");
+    }
+
+    if sim_pars.track_pars.f_lap_var < 0.0 {
+        return Err(InputValueError).context("f_lap_var must not be negative!");
+    }
+
+    if !(0.0..=1.0).contains(&sim_pars.track_pars.p_accident) {
+        return Err(InputValueError)
+            .context("p_accident is not within the required range [0.0, 1.0]!");
+    }
+
+    if sim_pars.track_pars.t_evo_per_carlap > 0.0 || sim_pars.track_pars.t_evo_max > 0.0 {
+        return Err(InputValueError)
+            .context("t_evo_per_carlap and t_evo_max must not be positive!");
+    }
+
+    if !(0.0..=1.0).contains(&sim_pars.track_pars.f_evo_reset_wet) {
+        return Err(InputValueError)
+            .context("f_evo_reset_wet is not within the required range [0.0, 1.0]!");
+    }
+
+    // DRIVERS -------------------------------------------------------------------------------------
+    for driver_pars in sim_pars.driver_pars_all.values() {
+        if driver_pars.t_lap_var_sigma < 0.0 {
+            return Err(InputValueError).context(format!(
+                "t_lap_var_sigma of driver {} must not be negative!",
+                driver_pars.initials
+            ));
+        }
+
+        if driver_pars.t_teamorder < 0.0 {
+            return Err(InputValueError).context(format!(
+                "t_teamorder of driver {} must not be negative!",
+                driver_pars.initials
+            ));
+        }
+
+        if let Some(start_perf_pars) = driver_pars.start_perf_pars.as_ref() {
+            if start_perf_pars.t_reaction_mean < 0.0
+                || start_perf_pars.t_reaction_sigma < 0.0
+                || start_perf_pars.t_launch_sigma < 0.0
+            {
+                return Err(InputValueError).context(format!(
+                    "t_reaction_mean, t_reaction_sigma, and t_launch_sigma of driver {} must not be \
+                    negative!",
+                    driver_pars.initials
+                ));
+            }
+        }
+
+        if let Some(weather_pars) = sim_pars.race_pars.weather_pars.as_ref() {
+            if let Some(compound) = driver_pars
+                .degr_pars_all
+                .keys()
+                .find(|&x| !weather_pars.compound_pars.contains_key(x))
+            {
+                return Err(InputValueError).context(format!(
+                    "The wetness parameters of compound {} (driver {}) are missing!",
+                    compound, driver_pars.initials
+                ));
+            }
+        }
+
+        if let Some(p_accident) = driver_pars.p_accident {
+            if !(0.0..=1.0).contains(&p_accident) {
+                return Err(InputValueError).context(format!(
+                    "p_accident of driver {} is not within the required range [0.0, 1.0]!",
+                    driver_pars.initials
+                ));
+            }
+        }
+    }
+
+    // CARS ----------------------------------------------------------------------------------------
+    let mut p_grids: Vec<u32> = sim_pars
+        .race_pars
+        .participants
+        .iter()
+        .filter_map(|car_no| sim_pars.car_pars_all.get(car_no))
+        .map(|car_pars| car_pars.p_grid)
+        .collect();
+    p_grids.sort_unstable();
+
+    if p_grids.first() == Some(&0) {
+        return Err(InputValueError).context("p_grid must be at least 1!");
+    }
+
+    if let Some(p_grid) = p_grids.windows(2).find(|x| x[0] == x[1]) {
+        return Err(InputValueError).context(format!(
+            "Grid position {} is assigned to several participants!",
+            p_grid[0]
+        ));
+    }
+
+    let regulations = Regulations::new(sim_pars.race_pars.season);
+
+    for car_pars in sim_pars.car_pars_all.values() {
+        if let Some(p_failure) = car_pars.p_failure {
+            if !(0.0..=1.0).contains(&p_failure) {
+                return Err(InputValueError).context(format!(
+                    "p_failure of car {} is not within the required range [0.0, 1.0]!",
+                    car_pars.car_no
+                ));
+            }
+        }
+
+        if let Some(dnf_lap) = car_pars.dnf_lap {
+            if dnf_lap < 1 || dnf_lap > sim_pars.race_pars.tot_no_laps {
+                return Err(InputValueError).context(format!(
+                    "dnf_lap of car {} is not within the required range [1, tot_no_laps]!",
+                    car_pars.car_no
+                ));
+            }
+        }
+
+        if car_pars
+            .penalties
+            .iter()
+            .any(|x| x.lap < 1 || x.lap > sim_pars.race_pars.tot_no_laps)
+        {
+            return Err(InputValueError).context(format!(
+                "A penalty lap of car {} is not within the required range [1, tot_no_laps]!",
+                car_pars.car_no
+            ));
+        }
+
+        if let Some(tiresets_available) = car_pars.tiresets_available.as_ref() {
+            if let Some(tire_category) = regulations.check_tire_allocation(tiresets_available) {
+                return Err(InputValueError).context(format!(
+                    "The available tire sets of car {} exceed the allocation of the {} season \
+                    ({:?} tires)!",
+                    car_pars.car_no, sim_pars.race_pars.season, tire_category
+                ));
+            }
+        }
+
+        if let Some(StrategyControllerPars::RuleBased(rule_based_pars)) =
+            car_pars.strategy_controller.as_ref()
+        {
+            if rule_based_pars.max_gap_undercut < 0.0 {
+                return Err(InputValueError).context(format!(
+                    "max_gap_undercut of car {} must not be negative!",
+                    car_pars.car_no
+                ));
+            }
+        }
+    }
+
     // STRATEGY ------------------------------------------------------------------------------------
     for car_pars in sim_pars.car_pars_all.values() {
         if !(car_pars.strategy.len() >= 1) {
@@ -103,6 +520,32 @@ pub fn check_sim_opts_pars(sim_opts: &SimOpts, sim_pars: &SimPars) -> anyhow::Re
                 ));
             }
         }
+
+        // check the planned tire sets against the regulations (compound rule, tire allocation)
+        if sim_pars.race_pars.regulation_pars.enforcement == Enforcement::Validation {
+            let tiresets_planned: Vec<TiresetPars> = car_pars
+                .strategy
+                .iter()
+                .filter(|x| !x.compound.is_empty())
+                .map(|x| TiresetPars {
+                    compound: x.compound.to_owned(),
+                    age: x.tire_start_age,
+                })
+                .collect();
+
+            let violations = regulations.check_tiresets(
+                &tiresets_planned,
+                car_pars.tiresets_available.as_deref(),
+                car_pars.dnf_lap.is_none(),
+            );
+
+            if let Some(violation) = violations.first() {
+                return Err(InputValueError).context(format!(
+                    "The strategy of car {} violates the regulations of the {} season ({})!",
+                    car_pars.car_no, sim_pars.race_pars.season, violation
+                ));
+            }
+        }
     }
 
     Ok(())
@@ -36,3 +36,114 @@ pub fn read_sim_pars(filepath: &Path) -> anyhow::Result<SimPars> {
     ))?;
     Ok(pars)
 }
+
+/// read_example_sim_pars reads the example parameter file that is shipped with the repository
+/// (used by the tests).
+#[cfg(test)]
+pub fn read_example_sim_pars() -> SimPars {
+    let filepath = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../input/parameters/pars_YasMarina_2017_example.json");
+    read_sim_pars(&filepath).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimPars;
+    use crate::core::regulations::Enforcement;
+    use std::fs::read_to_string;
+    use std::path::Path;
+
+    #[test]
+    fn test_read_sim_pars_defaults() {
+        // remove all optional parameters from the example parameter file, i.e. create a parameter
+        // file as it was used before the respective features were added
+        let filepath = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../input/parameters/pars_YasMarina_2017_example.json");
+        let mut pars: serde_json::Value =
+            serde_json::from_str(&read_to_string(filepath).unwrap()).unwrap();
+
+        for key in [
+            "t_dirty_air",
+            "dirty_air_window",
+            "f_degr_dirty_air",
+            "min_t_dist_sc",
+            "f_laptime_sc",
+            "f_laptime_vsc",
+            "t_vsc_ending",
+            "fcy_phases",
+            "p_failure",
+            "event_pars",
+            "regulation_pars",
+            "penalty_pars",
+            "weather_pars",
+            "team_order_pars",
+            "blue_flag_pars",
+            "start_pars",
+            "pit_stop_pars",
+            "pit_lane_pars",
+        ] {
+            pars["race_pars"].as_object_mut().unwrap().remove(key);
+        }
+
+        for key in [
+            "f_lap_var",
+            "p_accident",
+            "t_evo_per_carlap",
+            "t_evo_max",
+            "f_evo_reset_wet",
+            "overtaking_difficulty",
+            "k_vel_max",
+        ] {
+            pars["track_pars"].as_object_mut().unwrap().remove(key);
+        }
+
+        for driver_pars in pars["driver_pars_all"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
+        {
+            for key in ["t_lap_var_sigma", "p_accident", "start_perf_pars"] {
+                driver_pars.as_object_mut().unwrap().remove(key);
+            }
+        }
+
+        for car_pars in pars["car_pars_all"].as_object_mut().unwrap().values_mut() {
+            for key in [
+                "grid_penalty",
+                "p_failure",
+                "dnf_lap",
+                "strategy_controller",
+                "tiresets_available",
+                "penalties",
+            ] {
+                car_pars.as_object_mut().unwrap().remove(key);
+            }
+        }
+
+        let sim_pars: SimPars = serde_json::from_value(pars).unwrap();
+
+        assert_eq!(sim_pars.race_pars.t_dirty_air, 0.0);
+        assert!(sim_pars.race_pars.fcy_phases.is_empty());
+        assert_eq!(sim_pars.race_pars.p_failure, 0.0);
+        assert_eq!(
+            sim_pars.race_pars.regulation_pars.enforcement,
+            Enforcement::Ignore
+        );
+        assert_eq!(sim_pars.race_pars.penalty_pars.p_penalty, 0.0);
+        assert!(sim_pars.race_pars.blue_flag_pars.is_none());
+        assert!(sim_pars.race_pars.start_pars.is_none());
+        assert!(sim_pars.race_pars.pit_stop_pars.is_empty());
+        assert!(sim_pars.race_pars.pit_lane_pars.is_none());
+        assert_eq!(sim_pars.track_pars.f_lap_var, 0.0);
+        assert_eq!(sim_pars.track_pars.p_accident, 0.0);
+        assert!(sim_pars.track_pars.overtaking_difficulty.is_none());
+        assert!(sim_pars
+            .driver_pars_all
+            .values()
+            .all(|x| x.t_lap_var_sigma == 0.0));
+        assert!(sim_pars
+            .car_pars_all
+            .values()
+            .all(|x| x.penalties.is_empty()));
+    }
+}
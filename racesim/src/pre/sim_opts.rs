@@ -11,7 +11,8 @@ use std::path::PathBuf;
 #[clap(setting = AppSettings::ColoredHelp)]
 pub struct SimOpts {
     // FLAGS ---------------------------------------------------------------------------------------
-    /// Activate debug printing (not usable in case GUI/real-time simulation is activated)
+    /// Activate debug printing, i.e. print the complete race event log including overtakes, DRS
+    /// activations, and pit lane events (not usable in case GUI/real-time simulation is activated)
     #[clap(short, long, conflicts_with = "gui")]
     pub debug: bool,
 
@@ -20,10 +21,24 @@ pub struct SimOpts {
     pub gui: bool,
 
     // OPTIONS -------------------------------------------------------------------------------------
+    /// Set path to a file the race events of the simulation run(s) are written to (JSON lines
+    /// format, i.e. one event per line, written with the run index and seed of its race)
+    #[clap(parse(from_os_str), short, long)]
+    pub events_path: Option<PathBuf>,
+
+    /// Set path to a JSON file the aggregated result of the simulation run(s) is written to
+    #[clap(parse(from_os_str), short, long)]
+    pub mc_result_path: Option<PathBuf>,
+
     /// Set number of simulation runs (must be one in case GUI/real-time simulation is activated)
     #[clap(short, long, default_value = "1")]
     pub no_sim_runs: u32,
 
+    /// Set path to a file the race result(s) are written to (the format is determined by the file
+    /// extension, csv or json), every run is written with its run index and seed
+    #[clap(parse(from_os_str), short, long = "output")]
+    pub output_path: Option<PathBuf>,
+
     /// Set path to the simulation parameter file
     #[clap(parse(from_os_str), short, long)]
     pub parfile_path: PathBuf,
@@ -32,7 +47,53 @@ pub struct SimOpts {
     #[clap(short, long, default_value = "1.0")]
     pub realtime_factor: f64,
 
+    /// Set base seed of the random number generator (drawn randomly if not set). A single run uses
+    /// the seed directly, multiple runs use seeds derived from it and the run index
+    #[clap(short, long)]
+    pub seed: Option<u64>,
+
+    /// Set path to a JSON file the telemetry of the race is written to (only possible for a single
+    /// simulation run), i.e. the state of every car in every recorded time step (one array per car
+    /// and channel)
+    #[clap(parse(from_os_str), long)]
+    pub telemetry_path: Option<PathBuf>,
+
+    /// Set decimation of the telemetry recording, i.e. only every n-th time step is recorded
+    #[clap(long, default_value = "1")]
+    pub telemetry_decimation: u32,
+
     /// Set simulation timestep size in seconds, should be in the range [0.001, 1.0]
     #[clap(short, long, default_value = "0.2")]
     pub timestep_size: f64,
+
+    // SUBCOMMANDS ---------------------------------------------------------------------------------
+    #[clap(subcommand)]
+    pub subcmd: Option<SubCommand>,
+}
+
+#[derive(Debug, Clap, Clone)]
+pub enum SubCommand {
+    /// Optimize the pit stop strategy of a car using full race simulations (the number of
+    /// simulation runs per evaluated strategy is set by no_sim_runs)
+    OptStrategy(OptStrategyOpts),
+}
+
+#[derive(Debug, Clap, Clone)]
+pub struct OptStrategyOpts {
+    /// Set number of the car whose strategy is optimized
+    #[clap(short, long)]
+    pub car_no: u32,
+
+    /// Set maximum number of pit stops, should be in the range [1, 3]
+    #[clap(short, long, default_value = "3")]
+    pub max_no_stops: u32,
+
+    /// Set number of the best compound sequences per number of stops (according to the race time
+    /// estimation on a free track) that are evaluated by full race simulations
+    #[clap(short, long, default_value = "5")]
+    pub no_candidates: u32,
+
+    /// Set minimum stint length in laps
+    #[clap(short = 'l', long, default_value = "5")]
+    pub min_stint_length: u32,
 }